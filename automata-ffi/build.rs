@@ -0,0 +1,24 @@
+// Generates include/automata.h of the automata crate from its ffi module, so that the
+// declarations for C always match the Rust functions. The header is also checked in,
+// so that C programs can use it without building the crate first.
+
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let root_dir = crate_dir.parent().unwrap();
+    let source = root_dir.join("src").join("ffi.rs");
+
+    println!("cargo:rerun-if-changed={}", source.display());
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(source)
+        .generate()
+        .expect("Unable to generate the C header")
+        .write_to_file(root_dir.join("include").join("automata.h"));
+}
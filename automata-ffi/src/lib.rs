@@ -0,0 +1,5 @@
+// Builds the C interface of the automata crate into a shared and a static library.
+// The declarations for C are generated by build.rs into include/automata.h of the
+// automata crate.
+
+pub use automata::ffi::*;
@@ -0,0 +1,48 @@
+use automata::expression::Expression;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, LitStr};
+
+fn quote_state(state: Option<u32>) -> TokenStream2 {
+    match state {
+        Some(state) => quote!(::std::option::Option::Some(#state)),
+        None        => quote!(::std::option::Option::None)
+    }
+}
+
+// Compiles an expression into an automata::dfa::StaticDfa while the crate that uses
+// it is being compiled, so that nothing is built at runtime. Invalid expressions
+// are reported as compile errors.
+//
+// static KEYWORDS: StaticDfa = automaton!("if|else|while");
+#[proc_macro]
+pub fn automaton(input: TokenStream) -> TokenStream {
+    let pattern = parse_macro_input!(input as LitStr);
+
+    let expression = match Expression::parse(&pattern.value()) {
+        Ok(expression) => expression,
+        Err(error)     => {
+            let message = format!("invalid expression: {}", error);
+            return syn::Error::new(pattern.span(), message).to_compile_error().into();
+        }
+    };
+
+    let table = expression.dfa().to_table();
+    let alphabet = &table.alphabet;
+    let final_states = &table.final_states;
+    let transitions = table.transitions.iter().map(|to| quote_state(*to));
+    let initial_state = quote_state(table.initial_state);
+
+    let res = quote! {
+        ::automata::dfa::StaticDfa::from_static_parts(
+            &[#(#alphabet),*],
+            &[#(#final_states),*],
+            &[#(#transitions),*],
+            #initial_state
+        )
+    };
+
+    res.into()
+}
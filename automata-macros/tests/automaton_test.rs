@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod tests {
+    use automata::dfa::StaticDfa;
+    use automata::expression::Expression;
+    use automata_macros::automaton;
+
+    static KEYWORDS: StaticDfa = automaton!("if|else|while");
+
+    #[test]
+    fn static_automaton() {
+        assert!(KEYWORDS.matches("if"));
+        assert!(KEYWORDS.matches("else"));
+        assert!(KEYWORDS.matches("while"));
+
+        assert!(!KEYWORDS.matches("whil"));
+        assert!(!KEYWORDS.matches("for"));
+        assert!(!KEYWORDS.matches(""));
+    }
+
+    #[test]
+    fn automaton_agrees_with_expression() {
+        let dfa = automaton!("a+bc*|c(a|\\*)*");
+        let expression = Expression::parse("a+bc*|c(a|\\*)*").unwrap();
+
+        for text in ["ab", "aabcc", "c", "ca*a", "bc", "abca", "", "c**"].iter() {
+            assert_eq!(dfa.matches(text), expression.matches(text));
+        }
+    }
+
+    #[test]
+    fn automaton_state_count() {
+        let dfa = automaton!("abc");
+        assert_eq!(dfa.state_count(), Expression::new("abc").dfa().to_table().state_count());
+    }
+}
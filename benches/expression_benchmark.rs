@@ -17,7 +17,7 @@ fn match_text_with_realistic_example() {
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("expression generation and matching", |b| b.iter(|| match_text_with_realistic_example()));
+    c.bench_function("expression generation and matching", |b| b.iter(match_text_with_realistic_example));
 }
 
 criterion_group!(benches, criterion_benchmark);
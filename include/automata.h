@@ -0,0 +1,80 @@
+/* C interface of the automata crate, generated from src/ffi.rs by the build script of
+   automata-ffi. Build that crate after changing the interface instead of editing this file. */
+
+#ifndef AUTOMATA_H
+#define AUTOMATA_H
+
+typedef struct AutomataExpression AutomataExpression;
+
+#define AUTOMATA_OK 0
+
+#define AUTOMATA_ERROR_NULL_POINTER 1
+
+#define AUTOMATA_ERROR_INVALID_UTF8 2
+
+#define AUTOMATA_ERROR_MISSING_OPERAND 3
+
+#define AUTOMATA_ERROR_UNCLOSED_GROUP 4
+
+#define AUTOMATA_ERROR_UNMATCHED_PARENTHESIS 5
+
+#define AUTOMATA_ERROR_DANGLING_ESCAPE 6
+
+#define AUTOMATA_ERROR_INVALID_GROUP_NAME 7
+
+#define AUTOMATA_ERROR_DUPLICATE_GROUP_NAME 8
+
+#define AUTOMATA_ERROR_NESTING_TOO_DEEP 9
+
+#define AUTOMATA_ERROR_PANIC 10
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Builds an expression from a nul-terminated UTF-8 pattern and stores it in `*expression`.
+ Returns `AUTOMATA_OK` on success and an error code otherwise, in which case
+ `automata_last_error_message` describes the error.
+
+ # Safety
+
+ `pattern` must be null or a valid nul-terminated string and `expression` must be
+ null or valid for writes. The stored expression has to be freed with
+ `automata_expression_free`.
+ */
+int automata_expression_new(const char *pattern, AutomataExpression **expression);
+
+/*
+ Returns 1 if the expression matches the nul-terminated UTF-8 text and 0 if it doesn't,
+ if the arguments are invalid or if the library panicked. In the last two cases
+ `automata_last_error_message` describes the error.
+
+ # Safety
+
+ `expression` must be null or an expression returned by `automata_expression_new` that
+ hasn't been freed. `text` must be null or a valid nul-terminated string.
+ */
+int automata_expression_matches(const AutomataExpression *expression, const char *text);
+
+/*
+ Frees an expression. Does nothing if `expression` is null.
+
+ # Safety
+
+ `expression` must be null or an expression returned by `automata_expression_new`
+ that hasn't been freed yet.
+ */
+void automata_expression_free(AutomataExpression *expression);
+
+/*
+ Returns the message of the last error in the current thread or null if the last call
+ succeeded. The message is valid until the next call into the library from the same thread.
+ */
+const char *automata_last_error_message(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AUTOMATA_H */
@@ -0,0 +1,222 @@
+use automata::expression::Expression;
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+
+#[derive(Debug, Default, PartialEq)]
+struct Options {
+    whole_line: bool,
+    invert: bool,
+    count: bool,
+    line_numbers: bool,
+    pattern: String,
+    files: Vec<String>
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut positional = Vec::new();
+    let mut only_positional = false;
+
+    for arg in args {
+        if only_positional || !arg.starts_with('-') || arg == "-" {
+            positional.push(arg.clone());
+            continue;
+        }
+
+        if arg == "--" {
+            only_positional = true;
+            continue;
+        }
+
+        // Short flags can be combined like "-vn"
+        for flag in arg.chars().skip(1) {
+            match flag {
+                'x' => options.whole_line = true,
+                'v' => options.invert = true,
+                'c' => options.count = true,
+                'n' => options.line_numbers = true,
+                _   => return Err(format!("unknown option -{}", flag))
+            }
+        }
+    }
+
+    if positional.is_empty() {
+        return Err("missing pattern".to_string());
+    }
+
+    options.pattern = positional.remove(0);
+    options.files = positional;
+
+    Ok(options)
+}
+
+// Prints the selected lines of the reader and returns how many were selected
+fn grep_reader<R: BufRead, W: Write>(expression: &Expression, options: &Options, mut reader: R, name: Option<&str>, out: &mut W) -> io::Result<usize> {
+    let mut buffer = Vec::new();
+    let mut line_number = 0;
+    let mut selected = 0;
+
+    loop {
+        buffer.clear();
+
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+
+        line_number += 1;
+
+        if buffer.last() == Some(&b'\n') {
+            buffer.pop();
+        }
+
+        let line = String::from_utf8_lossy(&buffer);
+        let is_match = if options.whole_line { expression.matches(&line) } else { expression.contains_match(&line) };
+
+        if is_match == options.invert {
+            continue;
+        }
+
+        selected += 1;
+
+        if options.count {
+            continue;
+        }
+
+        if let Some(name) = name {
+            write!(out, "{}:", name)?;
+        }
+
+        if options.line_numbers {
+            write!(out, "{}:", line_number)?;
+        }
+
+        writeln!(out, "{}", line)?;
+    }
+
+    if options.count {
+        match name {
+            Some(name) => writeln!(out, "{}:{}", name, selected)?,
+            None       => writeln!(out, "{}", selected)?
+        }
+    }
+
+    Ok(selected)
+}
+
+// Works like grep's exit codes: 0 if any line was selected, 1 if none were and 2 on errors
+pub(crate) fn run(args: &[String]) -> i32 {
+    let options = match parse_options(args) {
+        Ok(options) => options,
+        Err(error)  => {
+            eprintln!("automata grep: {}", error);
+            return 2;
+        }
+    };
+
+    let expression = match Expression::parse(&options.pattern) {
+        Ok(expression) => expression,
+        Err(error)     => {
+            eprintln!("automata grep: invalid pattern: {}", error);
+            return 2;
+        }
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut selected = 0;
+    let mut failed = false;
+
+    if options.files.is_empty() {
+        let stdin = io::stdin();
+
+        match grep_reader(&expression, &options, stdin.lock(), None, &mut out) {
+            Ok(count) => selected += count,
+            Err(error) => {
+                eprintln!("automata grep: {}", error);
+                failed = true;
+            }
+        }
+    }
+
+    let show_names = options.files.len() > 1;
+
+    for file_name in &options.files {
+        let name = if show_names { Some(file_name.as_str()) } else { None };
+        let result = if file_name == "-" {
+            let stdin = io::stdin();
+            grep_reader(&expression, &options, stdin.lock(), name, &mut out)
+        }
+        else {
+            File::open(file_name).and_then(|file| grep_reader(&expression, &options, BufReader::new(file), name, &mut out))
+        };
+
+        match result {
+            Ok(count) => selected += count,
+            Err(error) => {
+                eprintln!("automata grep: {}: {}", file_name, error);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        2
+    }
+    else if selected > 0 {
+        0
+    }
+    else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn grep(options: &[&str], input: &str) -> String {
+        let options = parse_options(&args(options)).unwrap();
+        let expression = Expression::parse(&options.pattern).unwrap();
+        let mut out = Vec::new();
+
+        grep_reader(&expression, &options, input.as_bytes(), None, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn parse_combined_flags() {
+        let options = parse_options(&args(&["-vn", "-x", "ab+", "a.txt", "-"])).unwrap();
+
+        assert_eq!(options, Options {
+            whole_line: true,
+            invert: true,
+            count: false,
+            line_numbers: true,
+            pattern: "ab+".to_string(),
+            files: args(&["a.txt", "-"])
+        });
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(parse_options(&args(&["-q", "a"])), Err("unknown option -q".to_string()));
+        assert_eq!(parse_options(&args(&["-v"])), Err("missing pattern".to_string()));
+        assert_eq!(parse_options(&args(&["--", "-v"])).unwrap().pattern, "-v");
+    }
+
+    #[test]
+    fn grep_modes() {
+        let input = "abc\nxabbcx\nac\nab\n";
+
+        assert_eq!(grep(&["ab+c"], input), "abc\nxabbcx\n");
+        assert_eq!(grep(&["-x", "ab+c"], input), "abc\n");
+        assert_eq!(grep(&["-v", "ab+c"], input), "ac\nab\n");
+        assert_eq!(grep(&["-c", "ab+c"], input), "2\n");
+        assert_eq!(grep(&["-n", "ab+c"], input), "1:abc\n2:xabbcx\n");
+    }
+}
@@ -0,0 +1,164 @@
+use automata::dfa::DFA;
+use automata::nfa::NFA;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Stage {
+    Nfa,
+    Dfa,
+    Minimized
+}
+
+impl Stage {
+    fn command(self) -> &'static str {
+        match self {
+            Stage::Nfa       => "nfa",
+            Stage::Dfa       => "dfa",
+            Stage::Minimized => "min"
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Options {
+    dot: bool,
+    hide_dead_states: bool,
+    pattern: String
+}
+
+fn parse_options(stage: Stage, args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut positional = Vec::new();
+    let mut only_positional = false;
+
+    for arg in args {
+        if only_positional || !arg.starts_with('-') {
+            positional.push(arg.clone());
+            continue;
+        }
+
+        match arg.as_str() {
+            "--"          => only_positional = true,
+            "--dot"       => options.dot = true,
+            "--hide-dead" => {
+                // An NFA has no dead state to hide
+                if stage == Stage::Nfa {
+                    return Err(format!("option {} can't be used with {}", arg, stage.command()));
+                }
+
+                options.hide_dead_states = true;
+            },
+            _             => return Err(format!("unknown option {}", arg))
+        }
+    }
+
+    match positional.len() {
+        0 => Err("missing pattern".to_string()),
+        1 => {
+            options.pattern = positional.remove(0);
+            Ok(options)
+        },
+        _ => Err(format!("unexpected argument {}", positional[1]))
+    }
+}
+
+fn describe(name: &str, state_count: usize, transition_count: usize, table: String) -> String {
+    format!("{}: {} states, {} transitions\n\n{}", name, state_count, transition_count, table)
+}
+
+// Builds the automaton of the stage and returns its description
+fn inspect(stage: Stage, options: &Options) -> Result<String, String> {
+    let nfa = NFA::from_string(&options.pattern).map_err(|error| format!("invalid pattern: {}", error))?;
+
+    if stage == Stage::Nfa {
+        return Ok(if options.dot {
+            nfa.to_dot()
+        }
+        else {
+            describe("NFA", nfa.state_count(), nfa.transition_count(), nfa.to_string())
+        });
+    }
+
+    let mut dfa = DFA::from_nfa(nfa);
+    let mut name = "DFA";
+
+    if stage == Stage::Minimized {
+        dfa = dfa.minimize();
+        name = "Minimized DFA";
+    }
+
+    Ok(if options.dot {
+        dfa.to_dot(options.hide_dead_states)
+    }
+    else {
+        describe(name, dfa.state_count(), dfa.transition_count(), dfa.to_string())
+    })
+}
+
+pub(crate) fn run(stage: Stage, args: &[String]) -> i32 {
+    let result = parse_options(stage, args).and_then(|options| inspect(stage, &options));
+
+    match result {
+        Ok(output) => {
+            print!("{}", output);
+            0
+        },
+        Err(error) => {
+            eprintln!("automata {}: {}", stage.command(), error);
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(list: &[&str]) -> Options {
+        parse_options(Stage::Dfa, &list.iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(options(&["--dot", "a|b", "--hide-dead"]), Options {
+            dot: true,
+            hide_dead_states: true,
+            pattern: "a|b".to_string()
+        });
+        assert_eq!(options(&["--", "--dot"]).pattern, "--dot");
+        assert!(parse_options(Stage::Dfa, &[]).is_err());
+        assert!(parse_options(Stage::Dfa, &["-x".to_string(), "a".to_string()]).is_err());
+        assert!(parse_options(Stage::Dfa, &["a".to_string(), "b".to_string()]).is_err());
+        assert!(parse_options(Stage::Nfa, &["--hide-dead".to_string(), "a".to_string()]).is_err());
+        assert!(parse_options(Stage::Minimized, &["--hide-dead".to_string(), "a".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn stages() {
+        assert_eq!(inspect(Stage::Nfa, &options(&["ab"])).unwrap(), concat!(
+            "NFA: 4 states, 3 transitions\n",
+            "\n",
+            "     a b ε\n",
+            "   0 - 1 -\n",
+            " * 1 - - -\n",
+            "-> 2 3 - -\n",
+            "   3 - - 0\n"
+        ));
+
+        let dfa = inspect(Stage::Dfa, &options(&["ab"])).unwrap();
+        let minimized = inspect(Stage::Minimized, &options(&["ab"])).unwrap();
+
+        assert!(dfa.starts_with("DFA: "));
+        assert!(minimized.starts_with("Minimized DFA: 4 states, 8 transitions\n"));
+    }
+
+    #[test]
+    fn dot() {
+        assert!(inspect(Stage::Nfa, &options(&["--dot", "a*"])).unwrap().starts_with("digraph"));
+        assert!(inspect(Stage::Minimized, &options(&["--dot", "a*"])).unwrap().starts_with("digraph"));
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        assert!(inspect(Stage::Dfa, &options(&["(a"])).unwrap_err().starts_with("invalid pattern: "));
+    }
+}
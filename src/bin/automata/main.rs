@@ -0,0 +1,56 @@
+mod grep;
+mod inspect;
+mod repl;
+
+use inspect::Stage;
+
+use std::env;
+use std::process;
+
+const USAGE: &str = "\
+Usage: automata <command> [arguments]
+
+Commands:
+    grep [-x] [-v] [-c] [-n] <pattern> [file...]
+        Prints the lines that contain a match of the pattern.
+        Reads the standard input if no files are given.
+
+        -x    only match whole lines
+        -v    print the lines that don't match instead
+        -c    only print the number of printed lines
+        -n    print the line number before each line
+
+    nfa [--dot] <pattern>
+    dfa [--dot] [--hide-dead] <pattern>
+    min [--dot] [--hide-dead] <pattern>
+        Prints the state and transition counts and the transition table
+        of the pattern's NFA, its determinized DFA or the minimized DFA.
+
+        --dot          print a Graphviz DOT description instead
+        --hide-dead    leave the dead state out of the DOT description
+
+    repl [pattern]
+        Starts an interactive session for testing strings against a pattern.
+        Type :help in it to see the commands.";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let code = match args.first().map(String::as_str) {
+        Some("grep") => grep::run(&args[1..]),
+        Some("nfa")  => inspect::run(Stage::Nfa, &args[1..]),
+        Some("dfa")  => inspect::run(Stage::Dfa, &args[1..]),
+        Some("min")  => inspect::run(Stage::Minimized, &args[1..]),
+        Some("repl") => repl::run(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            0
+        },
+        _ => {
+            eprintln!("{}", USAGE);
+            2
+        }
+    };
+
+    process::exit(code);
+}
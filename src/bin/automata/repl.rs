@@ -0,0 +1,252 @@
+use automata::expression::{Expression, FailureKind};
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+const HELP: &str = "\
+Type a string to see whether the pattern matches it and which states it goes through.
+
+Commands:
+    :pattern [pattern]    sets the pattern or prints the current one
+    :dot                  prints a Graphviz DOT description of the pattern's DFA
+    :strings [n]          prints the first n matched strings (10 by default)
+    :equiv <other>        checks whether the other pattern matches the same strings
+    :match <text>         tests the text, even if it starts with ':'
+    :help                 prints this message
+    :quit                 exits";
+
+const DEFAULT_STRING_COUNT: usize = 10;
+
+struct Repl {
+    pattern: Option<(String, Expression)>
+}
+
+impl Repl {
+    fn new() -> Self {
+        Repl {
+            pattern: None
+        }
+    }
+
+    fn set_pattern<W: Write>(&mut self, pattern: &str, out: &mut W) -> io::Result<()> {
+        match Expression::parse(pattern) {
+            Ok(expression) => {
+                self.pattern = Some((pattern.to_string(), expression));
+                Ok(())
+            },
+            Err(error) => writeln!(out, "invalid pattern: {}", error)
+        }
+    }
+
+    // Handles a line of input and returns false if the session should end
+    fn handle<W: Write>(&mut self, line: &str, out: &mut W) -> io::Result<bool> {
+        if !line.starts_with(':') {
+            self.test(line, out)?;
+            return Ok(true);
+        }
+
+        let (command, argument) = match line.find(' ') {
+            Some(index) => (&line[..index], &line[index + 1..]),
+            None        => (line, "")
+        };
+
+        match command {
+            ":pattern" if argument.is_empty() => match &self.pattern {
+                Some((pattern, _)) => writeln!(out, "{}", pattern)?,
+                None               => writeln!(out, "no pattern is set")?
+            },
+            ":pattern" => self.set_pattern(argument, out)?,
+            ":match"   => self.test(argument, out)?,
+            ":dot" | ":strings" | ":equiv" => self.pattern_command(command, argument, out)?,
+            ":help"    => writeln!(out, "{}", HELP)?,
+            ":quit" | ":q" => return Ok(false),
+            _          => writeln!(out, "unknown command {}, see :help", command)?
+        }
+
+        Ok(true)
+    }
+
+    // Handles the commands that need a pattern to be set
+    fn pattern_command<W: Write>(&self, command: &str, argument: &str, out: &mut W) -> io::Result<()> {
+        let expression = match &self.pattern {
+            Some((_, expression)) => expression,
+            None                  => return writeln!(out, "set a pattern with :pattern first")
+        };
+
+        match command {
+            ":dot" => write!(out, "{}", expression.to_dot(true)),
+            ":strings" => {
+                let count = if argument.is_empty() { Ok(DEFAULT_STRING_COUNT) } else { argument.trim().parse() };
+
+                match count {
+                    Ok(count) => {
+                        for string in expression.strings().take(count) {
+                            writeln!(out, "{:?}", string)?;
+                        }
+
+                        Ok(())
+                    },
+                    Err(_) => writeln!(out, "invalid count {}", argument)
+                }
+            },
+            _ => {
+                let other = match Expression::parse(argument) {
+                    Ok(other)  => other,
+                    Err(error) => return writeln!(out, "invalid pattern: {}", error)
+                };
+
+                match expression.distinguishing_string(&other) {
+                    None => writeln!(out, "equivalent"),
+                    Some(string) => {
+                        let matched_by = if expression.matches(&string) { "only the current pattern" } else { "only the other pattern" };
+                        writeln!(out, "not equivalent: {:?} is matched by {}", string, matched_by)
+                    }
+                }
+            }
+        }
+    }
+
+    // Prints whether the text is matched together with the states it goes through
+    // and the reason it was rejected
+    fn test<W: Write>(&self, text: &str, out: &mut W) -> io::Result<()> {
+        let trace = match &self.pattern {
+            Some((_, expression)) => expression.trace(text),
+            None                  => return writeln!(out, "set a pattern with :pattern first")
+        };
+
+        let mut path = trace.initial_state.map_or("-".to_string(), |state| state.to_string());
+
+        for step in &trace.steps {
+            path.push_str(&format!(" -{}-> {}", step.symbol, step.after));
+        }
+
+        let failure = match trace.failure {
+            Some(failure) => failure,
+            None          => return writeln!(out, "accepted: {}", path)
+        };
+
+        let state = trace.last_state().map_or("-".to_string(), |state| state.to_string());
+        let reason = match failure.kind {
+            FailureKind::MissingTransition(symbol) => format!("no transition from state {} with {:?} at position {}", state, symbol, failure.position),
+            FailureKind::DeadState(symbol)         => format!("entered the dead state {} with {:?} at position {}", state, symbol, failure.position),
+            FailureKind::NonFinalState             => format!("ended in the non-final state {}", state)
+        };
+
+        writeln!(out, "rejected: {}\n    {}", path, reason)?;
+
+        match trace.longest_match {
+            Some(prefix) if !prefix.is_empty() => writeln!(out, "    the longest matched prefix is {:?}", prefix),
+            _ => Ok(())
+        }
+    }
+}
+
+pub(crate) fn run(args: &[String]) -> i32 {
+    let mut repl = Repl::new();
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let interactive = stdin.is_terminal();
+
+    if args.len() > 1 {
+        eprintln!("automata repl: unexpected argument {}", args[1]);
+        return 2;
+    }
+
+    if let Some(pattern) = args.first() {
+        let _ = repl.set_pattern(pattern, &mut out);
+    }
+
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        // Only prompt when someone is typing, so that piped output stays clean
+        if interactive {
+            let _ = write!(out, "> ");
+            let _ = out.flush();
+        }
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(error)) => {
+                eprintln!("automata repl: {}", error);
+                return 2;
+            },
+            None => return 0
+        };
+
+        match repl.handle(&line, &mut out) {
+            Ok(true)  => (),
+            Ok(false) => return 0,
+            Err(error) => {
+                eprintln!("automata repl: {}", error);
+                return 2;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(lines: &[&str]) -> String {
+        let mut repl = Repl::new();
+        let mut out = Vec::new();
+
+        for line in lines {
+            if !repl.handle(line, &mut out).unwrap() {
+                break;
+            }
+        }
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_strings() {
+        assert_eq!(session(&[":pattern ab", "ab", "a", "b", "ax", "ab"]), concat!(
+            "accepted: 1 -a-> 2 -b-> 3\n",
+            "rejected: 1 -a-> 2\n",
+            "    ended in the non-final state 2\n",
+            "rejected: 1 -b-> 0\n",
+            "    entered the dead state 0 with 'b' at position 0\n",
+            "rejected: 1 -a-> 2\n",
+            "    no transition from state 2 with 'x' at position 1\n",
+            "accepted: 1 -a-> 2 -b-> 3\n"
+        ));
+        assert_eq!(session(&[":pattern ab*", "abba"]), concat!(
+            "rejected: 1 -a-> 2 -b-> 2 -b-> 2 -a-> 0\n",
+            "    entered the dead state 0 with 'a' at position 3\n",
+            "    the longest matched prefix is \"abb\"\n"
+        ));
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(session(&[":pattern", ":strings", ":pattern a(b", ":pattern ab*", ":pattern"]), concat!(
+            "no pattern is set\n",
+            "set a pattern with :pattern first\n",
+            "invalid pattern: group opened at position 1 is never closed\n",
+            "ab*\n"
+        ));
+        assert_eq!(session(&[":pattern ab*", ":strings 3", ":match :x", ":unknown"]), concat!(
+            "\"a\"\n",
+            "\"ab\"\n",
+            "\"abb\"\n",
+            "rejected: 1\n",
+            "    no transition from state 1 with ':' at position 0\n",
+            "unknown command :unknown, see :help\n"
+        ));
+        assert!(session(&[":pattern a", ":dot"]).starts_with("digraph"));
+        assert_eq!(session(&[":quit", ":help"]), "");
+    }
+
+    #[test]
+    fn equiv() {
+        assert_eq!(session(&[":pattern ab*", ":equiv a|ab(b*)", ":equiv a|ab", ":equiv a(b|c)*"]), concat!(
+            "equivalent\n",
+            "not equivalent: \"abb\" is matched by only the current pattern\n",
+            "not equivalent: \"ac\" is matched by only the other pattern\n"
+        ));
+    }
+}
@@ -0,0 +1,404 @@
+use crate::dfa::DFA;
+use crate::table::DfaTable;
+
+use std::error::Error;
+use std::fmt;
+
+// The binary format of a DFA consists of a header followed by three sections.
+// All numbers are u32 in the byte order given in the header.
+//
+// header:       magic "ADFA", version (u16), byte order (u8), reserved (u8),
+//               state count, alphabet size, initial state
+// alphabet:     the alphabet's chars sorted in ascending order
+// final states: a bit set with a bit for each state
+// transitions:  a table with a row for each state and a column for each char
+//               of the alphabet, holding the state that the transition leads to
+//
+// The states are numbered by their order in the DFA. A missing transition or
+// a missing initial state is stored as NO_STATE.
+const MAGIC: &[u8; 4] = b"ADFA";
+const VERSION: u16 = 1;
+const LITTLE_ENDIAN: u8 = 1;
+const BIG_ENDIAN: u8 = 2;
+const HEADER_SIZE: usize = 20;
+const NO_STATE: u32 = u32::MAX;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormatError {
+    // The data is shorter than the header
+    TooShort,
+    BadMagic,
+    UnsupportedVersion(u16),
+    UnknownByteOrder(u8),
+
+    // The data's length doesn't match the sizes in the header
+    LengthMismatch { expected: usize, actual: usize },
+
+    // A number in the alphabet that isn't a valid char
+    InvalidChar(u32),
+
+    // The alphabet is not sorted or has duplicates
+    UnsortedAlphabet,
+
+    // A state number that is not less than the state count
+    InvalidState(u32)
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::TooShort                           => write!(f, "data is too short to contain a header"),
+            FormatError::BadMagic                           => write!(f, "data doesn't start with the DFA magic bytes"),
+            FormatError::UnsupportedVersion(version)        => write!(f, "unsupported format version {}", version),
+            FormatError::UnknownByteOrder(order)            => write!(f, "unknown byte order {}", order),
+            FormatError::LengthMismatch { expected, actual } => write!(f, "expected {} bytes, but got {}", expected, actual),
+            FormatError::InvalidChar(value)                 => write!(f, "{:#x} is not a valid char", value),
+            FormatError::UnsortedAlphabet                   => write!(f, "alphabet is not sorted"),
+            FormatError::InvalidState(state)                => write!(f, "state {} is out of range", state)
+        }
+    }
+}
+
+impl Error for FormatError {}
+
+fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_ne_bytes());
+}
+
+// Encodes the DFA in the native byte order
+pub(crate) fn to_bytes(dfa: &DFA) -> Vec<u8> {
+    let table = DfaTable::from_dfa(dfa);
+    let state_count = table.state_count();
+
+    let mut bytes = Vec::new();
+
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_ne_bytes());
+    bytes.push(if cfg!(target_endian = "big") { BIG_ENDIAN } else { LITTLE_ENDIAN });
+    bytes.push(0);
+    push_u32(&mut bytes, state_count as u32);
+    push_u32(&mut bytes, table.alphabet.len() as u32);
+    push_u32(&mut bytes, table.initial_state.unwrap_or(NO_STATE));
+
+    for letter in &table.alphabet {
+        push_u32(&mut bytes, *letter as u32);
+    }
+
+    let mut final_bits = vec![0u32; state_count.div_ceil(32)];
+
+    for (state, is_final) in table.final_states.iter().enumerate() {
+        if *is_final {
+            final_bits[state / 32] |= 1 << (state % 32);
+        }
+    }
+
+    for word in final_bits {
+        push_u32(&mut bytes, word);
+    }
+
+    for to in &table.transitions {
+        push_u32(&mut bytes, to.unwrap_or(NO_STATE));
+    }
+
+    bytes
+}
+
+// A DFA that is read directly from its binary encoding without copying it.
+// The data is validated once when the DfaRef is created, so matching
+// doesn't need to check it again.
+#[derive(Clone, Copy, Debug)]
+pub struct DfaRef<'a> {
+    bytes: &'a [u8],
+    big_endian: bool,
+    state_count: usize,
+    alphabet_size: usize,
+    initial_state: u32
+}
+
+impl<'a> DfaRef<'a> {
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<DfaRef<'a>, FormatError> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(FormatError::TooShort);
+        }
+
+        if &bytes[0..4] != MAGIC {
+            return Err(FormatError::BadMagic);
+        }
+
+        let big_endian = match bytes[6] {
+            LITTLE_ENDIAN => false,
+            BIG_ENDIAN    => true,
+            order         => return Err(FormatError::UnknownByteOrder(order))
+        };
+
+        let version_bytes = [bytes[4], bytes[5]];
+        let version = if big_endian { u16::from_be_bytes(version_bytes) } else { u16::from_le_bytes(version_bytes) };
+
+        if version != VERSION {
+            return Err(FormatError::UnsupportedVersion(version));
+        }
+
+        let mut dfa = DfaRef {
+            bytes,
+            big_endian,
+            state_count: 0,
+            alphabet_size: 0,
+            initial_state: NO_STATE
+        };
+
+        dfa.state_count = dfa.read_u32(8) as usize;
+        dfa.alphabet_size = dfa.read_u32(12) as usize;
+        dfa.initial_state = dfa.read_u32(16);
+
+        let expected = dfa.expected_length().unwrap_or(usize::MAX);
+
+        if expected != bytes.len() {
+            return Err(FormatError::LengthMismatch { expected, actual: bytes.len() });
+        }
+
+        dfa.validate()?;
+        Ok(dfa)
+    }
+
+    fn expected_length(&self) -> Option<usize> {
+        let words = self.state_count.checked_mul(self.alphabet_size)?
+                                    .checked_add(self.alphabet_size)?
+                                    .checked_add(self.state_count.div_ceil(32))?;
+
+        words.checked_mul(4)?.checked_add(HEADER_SIZE)
+    }
+
+    fn validate(&self) -> Result<(), FormatError> {
+        let mut previous: Option<u32> = None;
+
+        for i in 0..self.alphabet_size {
+            let value = self.letter(i);
+
+            if std::char::from_u32(value).is_none() {
+                return Err(FormatError::InvalidChar(value));
+            }
+
+            if previous.is_some_and(|p| p >= value) {
+                return Err(FormatError::UnsortedAlphabet);
+            }
+
+            previous = Some(value);
+        }
+
+        if self.initial_state != NO_STATE && self.initial_state as usize >= self.state_count {
+            return Err(FormatError::InvalidState(self.initial_state));
+        }
+
+        for i in 0..self.state_count * self.alphabet_size {
+            let to = self.read_u32(self.table_offset() + i * 4);
+
+            if to != NO_STATE && to as usize >= self.state_count {
+                return Err(FormatError::InvalidState(to));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_u32(&self, offset: usize) -> u32 {
+        let mut word = [0; 4];
+        word.copy_from_slice(&self.bytes[offset..offset + 4]);
+
+        if self.big_endian {
+            u32::from_be_bytes(word)
+        }
+        else {
+            u32::from_le_bytes(word)
+        }
+    }
+
+    fn letter(&self, index: usize) -> u32 {
+        self.read_u32(HEADER_SIZE + index * 4)
+    }
+
+    fn final_bits_offset(&self) -> usize {
+        HEADER_SIZE + self.alphabet_size * 4
+    }
+
+    fn table_offset(&self) -> usize {
+        self.final_bits_offset() + self.state_count.div_ceil(32) * 4
+    }
+
+    // Finds the letter's column in the transition table with a binary search of the alphabet
+    fn letter_index(&self, letter: char) -> Option<usize> {
+        let wanted = letter as u32;
+        let mut low = 0;
+        let mut high = self.alphabet_size;
+
+        while low < high {
+            let middle = (low + high) / 2;
+            let value = self.letter(middle);
+
+            if value == wanted {
+                return Some(middle);
+            }
+            else if value < wanted {
+                low = middle + 1;
+            }
+            else {
+                high = middle;
+            }
+        }
+
+        None
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.state_count
+    }
+
+    // Returns false for states that don't exist
+    pub fn is_final(&self, state: u32) -> bool {
+        if state as usize >= self.state_count {
+            return false;
+        }
+
+        let word = self.read_u32(self.final_bits_offset() + state as usize / 32 * 4);
+        word & (1 << (state % 32)) != 0
+    }
+
+    // Returns the state that the transition with the letter leads to or None if
+    // there is no such transition or state
+    pub fn reachable(&self, state: u32, letter: char) -> Option<u32> {
+        if state as usize >= self.state_count {
+            return None;
+        }
+
+        let index = self.letter_index(letter)?;
+        let to = self.read_u32(self.table_offset() + (state as usize * self.alphabet_size + index) * 4);
+
+        if to == NO_STATE {
+            None
+        }
+        else {
+            Some(to)
+        }
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        if self.initial_state == NO_STATE {
+            return false;
+        }
+
+        let mut current_state = self.initial_state;
+
+        for ch in text.chars() {
+            match self.reachable(current_state, ch) {
+                Some(state) => current_state = state,
+                None        => return false
+            }
+        }
+
+        self.is_final(current_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nfa::NFA;
+
+    fn build_bytes(string: &str) -> Vec<u8> {
+        to_bytes(&DFA::from_nfa(NFA::from_string(string).unwrap()).minimize())
+    }
+
+    #[test]
+    fn header() {
+        let bytes = build_bytes("ab");
+        let dfa = DfaRef::from_bytes(&bytes).unwrap();
+
+        assert_eq!(&bytes[0..4], b"ADFA");
+        assert_eq!(dfa.state_count(), 4);
+        assert_eq!(dfa.alphabet_size, 2);
+        assert_eq!(bytes.len(), HEADER_SIZE + (2 + 1 + 4 * 2) * 4);
+    }
+
+    #[test]
+    fn matches() {
+        let bytes = build_bytes("a+bc*|ca*");
+        let dfa = DfaRef::from_bytes(&bytes).unwrap();
+
+        assert!(dfa.matches("ab"));
+        assert!(dfa.matches("aabcc"));
+        assert!(dfa.matches("caa"));
+        assert!(!dfa.matches("bc"));
+        assert!(!dfa.matches("abd"));
+        assert!(!dfa.matches(""));
+    }
+
+    #[test]
+    fn states_out_of_range() {
+        let bytes = build_bytes("ab");
+        let dfa = DfaRef::from_bytes(&bytes).unwrap();
+
+        assert_eq!(dfa.state_count(), 4);
+        assert!(!dfa.is_final(4));
+        assert!(!dfa.is_final(u32::MAX));
+        assert_eq!(dfa.reachable(4, 'a'), None);
+        assert_eq!(dfa.reachable(u32::MAX, 'b'), None);
+    }
+
+    #[test]
+    fn other_byte_order() {
+        let bytes = build_bytes("ab|c");
+        let big_endian = cfg!(target_endian = "big");
+        let mut swapped = bytes[0..4].to_vec();
+
+        swapped.push(bytes[5]);
+        swapped.push(bytes[4]);
+        swapped.push(if big_endian { LITTLE_ENDIAN } else { BIG_ENDIAN });
+        swapped.push(0);
+
+        for word in bytes[8..].chunks(4) {
+            swapped.extend(word.iter().rev());
+        }
+
+        let dfa = DfaRef::from_bytes(&swapped).unwrap();
+
+        assert!(dfa.matches("ab"));
+        assert!(dfa.matches("c"));
+        assert!(!dfa.matches("a"));
+    }
+
+    #[test]
+    fn corrupt_data() {
+        let bytes = build_bytes("ab|c");
+
+        assert_eq!(DfaRef::from_bytes(&bytes[..10]).unwrap_err(), FormatError::TooShort);
+        assert_eq!(DfaRef::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(), FormatError::LengthMismatch { expected: bytes.len(), actual: bytes.len() - 1 });
+
+        let mut corrupt = bytes.clone();
+        corrupt[0] = b'X';
+        assert_eq!(DfaRef::from_bytes(&corrupt).unwrap_err(), FormatError::BadMagic);
+
+        let mut corrupt = bytes.clone();
+        corrupt[6] = 7;
+        assert_eq!(DfaRef::from_bytes(&corrupt).unwrap_err(), FormatError::UnknownByteOrder(7));
+
+        let mut corrupt = bytes.clone();
+        corrupt[4..6].copy_from_slice(&2u16.to_ne_bytes());
+        assert_eq!(DfaRef::from_bytes(&corrupt).unwrap_err(), FormatError::UnsupportedVersion(2));
+
+        let mut corrupt = bytes.clone();
+        corrupt[12..16].copy_from_slice(&1000u32.to_ne_bytes());
+        assert!(matches!(DfaRef::from_bytes(&corrupt).unwrap_err(), FormatError::LengthMismatch { .. }));
+
+        let mut corrupt = bytes.clone();
+        corrupt[HEADER_SIZE..HEADER_SIZE + 4].copy_from_slice(&0xD800u32.to_ne_bytes());
+        assert_eq!(DfaRef::from_bytes(&corrupt).unwrap_err(), FormatError::InvalidChar(0xD800));
+
+        let mut corrupt = bytes.clone();
+        corrupt[HEADER_SIZE..HEADER_SIZE + 4].copy_from_slice(&('z' as u32).to_ne_bytes());
+        assert_eq!(DfaRef::from_bytes(&corrupt).unwrap_err(), FormatError::UnsortedAlphabet);
+
+        let mut corrupt = bytes.clone();
+        let last = corrupt.len() - 4;
+        corrupt[last..].copy_from_slice(&99u32.to_ne_bytes());
+        assert_eq!(DfaRef::from_bytes(&corrupt).unwrap_err(), FormatError::InvalidState(99));
+    }
+}
@@ -0,0 +1,242 @@
+use crate::nfa::NFA;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use std::cell::OnceCell;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+// The positions where each group started and ended on the way to a state. The
+// start of group i is at index 2 * i and its end right after it.
+type Tags = Vec<Option<usize>>;
+
+// The capture groups of an expression. They are found with the automaton that the
+// parser builds, which has a state of its own where each group starts and one where
+// it ends. The minimized DFA has no such states, so it can only find the matches.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct CaptureGroups {
+    nfa: NFA,
+    // The group that starts or ends at a state
+    starts: BTreeMap<u32, usize>,
+    ends: BTreeMap<u32, usize>,
+    names: Vec<Option<String>>
+}
+
+impl CaptureGroups {
+    // Takes the automaton and the group names that Parser::parse_captures returns
+    pub(crate) fn new(nfa: NFA, names: Vec<Option<String>>) -> Self {
+        CaptureGroups {
+            starts: nfa.groups.iter().enumerate().map(|(group, (start, _))| (*start, group)).collect(),
+            ends: nfa.groups.iter().enumerate().map(|(group, (_, end))| (*end, group)).collect(),
+            nfa,
+            names
+        }
+    }
+
+    // Returns the number of groups, not counting the whole match
+    pub(crate) fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    // Returns the number of the group with the name, counting from 1
+    pub(crate) fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|other| other.as_deref() == Some(name)).map(|index| index + 1)
+    }
+
+    // Returns the spans of the groups in text[range], which has to be a match of the
+    // whole expression. All the ways through the automaton are followed at once. Of
+    // two ways that reach the same state, the one where the first group they differ in
+    // starts earlier, or starts at the same position and ends later, is kept. Since
+    // both continue the same way from there, that's the one the match ends with.
+    pub(crate) fn spans(&self, text: &str, range: Range<usize>) -> Vec<Option<Range<usize>>> {
+        let mut current_states = BTreeMap::new();
+
+        for state in &self.nfa.initial_states {
+            self.add_state(&mut current_states, *state, vec![None; 2 * self.len()], range.start);
+        }
+
+        let mut end = range.start;
+
+        for ch in text[range.clone()].chars() {
+            end += ch.len_utf8();
+            let mut next_states = BTreeMap::new();
+
+            for (state, tags) in &current_states {
+                for next in self.nfa.reachable(*state, Some(ch)) {
+                    self.add_state(&mut next_states, next, tags.clone(), end);
+                }
+            }
+
+            current_states = next_states;
+        }
+
+        let tags = current_states.into_iter()
+                                 .filter(|(state, _)| self.nfa.final_states.contains(state))
+                                 .map(|(_, tags)| tags)
+                                 .min_by_key(preference)
+                                 .unwrap_or_default();
+
+        let groups = tags.chunks(2).map(|tags| match tags {
+            // A deserialized automaton could end a group before it starts
+            [Some(start), Some(end)] if start <= end => Some(*start..*end),
+            _                                        => None
+        });
+
+        std::iter::once(Some(range)).chain(groups).collect()
+    }
+
+    // Adds the state and the states that it reaches through epsilon transitions at the
+    // position, unless another way to them is preferred
+    fn add_state(&self, states: &mut BTreeMap<u32, Tags>, state: u32, tags: Tags, position: usize) {
+        let mut stack = vec![(state, tags)];
+
+        while let Some((state, mut tags)) = stack.pop() {
+            if let Some(group) = self.starts.get(&state) {
+                // Entering a group again starts it over
+                set_tag(&mut tags, 2 * group, Some(position));
+                set_tag(&mut tags, 2 * group + 1, None);
+            }
+
+            if let Some(group) = self.ends.get(&state) {
+                set_tag(&mut tags, 2 * group + 1, Some(position));
+            }
+
+            if states.get(&state).is_some_and(|other| preference(other) <= preference(&tags)) {
+                continue;
+            }
+
+            for next in self.nfa.reachable(state, None) {
+                stack.push((next, tags.clone()));
+            }
+
+            states.insert(state, tags);
+        }
+    }
+}
+
+fn set_tag(tags: &mut Tags, index: usize, position: Option<usize>) {
+    if let Some(tag) = tags.get_mut(index) {
+        *tag = position;
+    }
+}
+
+// Orders the tags from the most to the least preferred. Groups that take part in the
+// match come before those that don't and a group that has ended comes before one that
+// hasn't yet.
+fn preference(tags: &Tags) -> Vec<(usize, Reverse<usize>)> {
+    tags.chunks(2).map(|tags| match tags {
+        [start, end] => (start.unwrap_or(usize::MAX), Reverse(end.map_or(0, |end| end + 1))),
+        _            => (usize::MAX, Reverse(0))
+    }).collect()
+}
+
+// A match and the spans of the capture groups in it. Group 0 is the whole match and the
+// other groups are numbered from 1 by their '(' from left to right. The spans of the
+// groups are only computed when a group other than 0 is asked for.
+#[derive(Debug)]
+pub struct Captures<'a> {
+    // None if the expression has no groups
+    groups: Option<&'a CaptureGroups>,
+    text: &'a str,
+    range: Range<usize>,
+    spans: OnceCell<Vec<Option<Range<usize>>>>
+}
+
+impl<'a> Captures<'a> {
+    pub(crate) fn new(groups: Option<&'a CaptureGroups>, text: &'a str, range: Range<usize>) -> Self {
+        Captures {
+            groups,
+            text,
+            range,
+            spans: OnceCell::new()
+        }
+    }
+
+    // Returns the number of groups, including the whole match
+    pub fn group_count(&self) -> usize {
+        self.groups.map_or(0, CaptureGroups::len) + 1
+    }
+
+    // Returns the byte range of the group in the text. It's None if there is no such
+    // group or if the group is not part of the match, like the group of "(a)|b" in "b".
+    // If a group matched several times, like the group of "(a|b)*", its last match is used.
+    pub fn range(&self, index: usize) -> Option<Range<usize>> {
+        match (index, self.groups) {
+            (0, _)            => Some(self.range.clone()),
+            (_, Some(groups)) => self.spans.get_or_init(|| groups.spans(self.text, self.range.clone())).get(index).cloned().flatten(),
+            (_, None)         => None
+        }
+    }
+
+    // Returns the text of the group. See range.
+    pub fn get(&self, index: usize) -> Option<&'a str> {
+        self.range(index).map(|range| &self.text[range])
+    }
+
+    // Returns the text of the group with the name. See range.
+    pub fn name(&self, name: &str) -> Option<&'a str> {
+        self.index_of(name).and_then(|index| self.get(index))
+    }
+
+    // Returns the number of the group with the name, if there is one
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.groups.and_then(|groups| groups.index_of(name))
+    }
+
+    // Returns the text of the whole match
+    pub fn as_str(&self) -> &'a str {
+        &self.text[self.range.clone()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn groups(pattern: &str) -> CaptureGroups {
+        let (nfa, names) = Parser::new(pattern).parse_captures().unwrap();
+        CaptureGroups::new(nfa, names)
+    }
+
+    fn spans(pattern: &str, text: &str) -> Vec<Option<Range<usize>>> {
+        groups(pattern).spans(text, 0..text.len())
+    }
+
+    #[test]
+    fn group_spans() {
+        assert_eq!(spans("(a+)(b*)", "aab"), vec![Some(0..3), Some(0..2), Some(2..3)]);
+        assert_eq!(spans("(a)|(b)", "b"), vec![Some(0..1), None, Some(0..1)]);
+        assert_eq!(spans("x(?<inner>(y)z)", "xyz"), vec![Some(0..3), Some(1..3), Some(1..2)]);
+        assert_eq!(spans("a(b?)c", "ac"), vec![Some(0..2), Some(1..1)]);
+        assert_eq!(spans("ab", "ab"), vec![Some(0..2)]);
+    }
+
+    #[test]
+    fn last_repetition_is_captured() {
+        assert_eq!(spans("(a|b)*", "abb"), vec![Some(0..3), Some(2..3)]);
+        assert_eq!(spans("((a|b)c)+", "acbc"), vec![Some(0..4), Some(2..4), Some(2..3)]);
+        assert_eq!(spans("(a*)*", "aa"), vec![Some(0..2), Some(0..2)]);
+        assert_eq!(spans("(a)*", ""), vec![Some(0..0), None]);
+    }
+
+    #[test]
+    fn earlier_groups_start_first_and_are_longest() {
+        assert_eq!(spans("(a*)(a*)", "aa"), vec![Some(0..2), Some(0..2), Some(2..2)]);
+        assert_eq!(spans("(a|ab)(b?)", "ab"), vec![Some(0..2), Some(0..2), Some(2..2)]);
+        assert_eq!(spans("a*(a)?", "aa"), vec![Some(0..2), Some(1..2)]);
+    }
+
+    #[test]
+    fn group_names() {
+        let groups = groups("(?<year>a+)-((?<day>b))");
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups.index_of("year"), Some(1));
+        assert_eq!(groups.index_of("day"), Some(3));
+        assert_eq!(groups.index_of("month"), None);
+    }
+}
@@ -0,0 +1,340 @@
+use crate::dfa::DFA;
+
+use std::collections::{BTreeSet, BTreeMap};
+use std::fmt::Write;
+
+// Groups the live transitions of each state by the state they lead to and turns
+// the labels of each group into ranges of consecutive chars. Transitions into
+// dead states are left out, because the generated code rejects the text as soon
+// as it would enter one.
+pub(crate) fn transition_ranges(dfa: &DFA) -> BTreeMap<u32, Vec<(char, char, u32)>> {
+    let live_states = dfa.live_states();
+    let mut res = BTreeMap::new();
+
+    for state in dfa.useful_states() {
+        let mut labels_by_target = BTreeMap::<u32, BTreeSet<char>>::new();
+
+        for transition in dfa.transitions_from(state) {
+            if live_states.contains(&transition.to) {
+                labels_by_target.entry(transition.to).or_default().insert(transition.label);
+            }
+        }
+
+        let mut ranges = Vec::new();
+
+        for (to, labels) in labels_by_target {
+            let mut labels = labels.into_iter();
+            let mut start = match labels.next() {
+                Some(letter) => letter,
+                None         => continue
+            };
+            let mut end = start;
+
+            for letter in labels {
+                if end as u32 + 1 == letter as u32 {
+                    end = letter;
+                }
+                else {
+                    ranges.push((start, end, to));
+                    start = letter;
+                    end = letter;
+                }
+            }
+
+            ranges.push((start, end, to));
+        }
+
+        ranges.sort();
+        res.insert(state, ranges);
+    }
+
+    res
+}
+
+// Turns sorted states into match patterns, joining consecutive states into ranges like "4..=6"
+fn state_patterns(states: impl Iterator<Item = u32>) -> Vec<String> {
+    let mut runs: Vec<(u32, u32)> = Vec::new();
+
+    for state in states {
+        match runs.last_mut() {
+            Some((_, end)) if *end + 1 == state => *end = state,
+            _ => runs.push((state, state))
+        }
+    }
+
+    runs.into_iter().map(|(start, end)| {
+        if start == end {
+            start.to_string()
+        }
+        else {
+            format!("{}..={}", start, end)
+        }
+    }).collect()
+}
+
+// Generates a standalone Rust function that matches the same strings as the DFA
+// by matching on the current state and char, without any tables
+pub(crate) fn to_rust_source(dfa: &DFA, fn_name: &str) -> String {
+    let useful_states = dfa.useful_states();
+    let ranges = transition_ranges(dfa);
+    let final_states = state_patterns(dfa.final_states.intersection(&useful_states).cloned());
+    let has_transitions = ranges.values().any(|r| !r.is_empty());
+    let mut res = String::new();
+
+    // Writing into a String can't fail, so the results are ignored
+    let initial_state = match dfa.initial_state.filter(|s| useful_states.contains(s)) {
+        Some(state) => state,
+        None        => {
+            let _ = writeln!(res, "pub fn {}(_text: &str) -> bool {{", fn_name);
+            let _ = writeln!(res, "    false");
+            let _ = writeln!(res, "}}");
+            return res;
+        }
+    };
+
+    if !has_transitions {
+        let _ = writeln!(res, "pub fn {}(text: &str) -> bool {{", fn_name);
+        let _ = writeln!(res, "    text.is_empty()");
+        let _ = writeln!(res, "}}");
+        return res;
+    }
+
+    let _ = writeln!(res, "pub fn {}(text: &str) -> bool {{", fn_name);
+    let _ = writeln!(res, "    let mut state: u32 = {};", initial_state);
+    let _ = writeln!(res);
+    let _ = writeln!(res, "    for ch in text.chars() {{");
+    let _ = writeln!(res, "        state = match (state, ch) {{");
+
+    for (from, state_ranges) in &ranges {
+        for (start, end, to) in state_ranges {
+            if start == end {
+                let _ = writeln!(res, "            ({}, {:?}) => {},", from, start, to);
+            }
+            else {
+                let _ = writeln!(res, "            ({}, {:?}..={:?}) => {},", from, start, end, to);
+            }
+        }
+    }
+
+    let _ = writeln!(res, "            _ => return false");
+    let _ = writeln!(res, "        }};");
+    let _ = writeln!(res, "    }}");
+    let _ = writeln!(res);
+
+    if final_states.len() == 1 && !final_states[0].contains("..") {
+        let _ = writeln!(res, "    state == {}", final_states[0]);
+    }
+    else {
+        let _ = writeln!(res, "    matches!(state, {})", final_states.join(" | "));
+    }
+
+    let _ = writeln!(res, "}}");
+    res
+}
+
+// Generates a self-contained C function with the given name that matches the same
+// nul-terminated UTF-8 strings as the DFA. Text that is not valid UTF-8 is not matched.
+pub(crate) fn to_c_source(dfa: &DFA, fn_name: &str) -> String {
+    let useful_states = dfa.useful_states();
+    let ranges = transition_ranges(dfa);
+    let final_states: Vec<String> = dfa.final_states.intersection(&useful_states).map(|s| format!("state == {}", s)).collect();
+    let mut res = String::new();
+
+    // Writing into a String can't fail, so the results are ignored
+    let _ = writeln!(res, "#include <stdbool.h>");
+    let _ = writeln!(res, "#include <stdint.h>");
+    let _ = writeln!(res);
+    let _ = writeln!(res, "/* Decodes the UTF-8 char at *text and moves *text after it. Returns 0xFFFFFFFF");
+    let _ = writeln!(res, "   without moving if the bytes are not valid UTF-8. A sequence that is cut short");
+    let _ = writeln!(res, "   by the nul is invalid too, because the nul is not a continuation byte. */");
+    let _ = writeln!(res, "static uint32_t {}_next_char(const unsigned char **text) {{", fn_name);
+    let _ = writeln!(res, "    const unsigned char *p = *text;");
+    let _ = writeln!(res, "    uint32_t ch;");
+    let _ = writeln!(res, "    int length, i;");
+    let _ = writeln!(res);
+    let _ = writeln!(res, "    if (p[0] < 0x80) {{");
+    let _ = writeln!(res, "        *text = p + 1;");
+    let _ = writeln!(res, "        return p[0];");
+    let _ = writeln!(res, "    }} else if (p[0] >= 0xC2 && p[0] < 0xE0) {{");
+    let _ = writeln!(res, "        ch = p[0] & 0x1F;");
+    let _ = writeln!(res, "        length = 2;");
+    let _ = writeln!(res, "    }} else if (p[0] >= 0xE0 && p[0] < 0xF0) {{");
+    let _ = writeln!(res, "        ch = p[0] & 0x0F;");
+    let _ = writeln!(res, "        length = 3;");
+    let _ = writeln!(res, "    }} else if (p[0] >= 0xF0 && p[0] < 0xF5) {{");
+    let _ = writeln!(res, "        ch = p[0] & 0x07;");
+    let _ = writeln!(res, "        length = 4;");
+    let _ = writeln!(res, "    }} else {{");
+    let _ = writeln!(res, "        return 0xFFFFFFFF;");
+    let _ = writeln!(res, "    }}");
+    let _ = writeln!(res);
+    let _ = writeln!(res, "    /* Stops at the first byte that is not 10xxxxxx, so nothing after the nul is read */");
+    let _ = writeln!(res, "    for (i = 1; i < length; i++) {{");
+    let _ = writeln!(res, "        if ((p[i] & 0xC0) != 0x80) {{");
+    let _ = writeln!(res, "            return 0xFFFFFFFF;");
+    let _ = writeln!(res, "        }}");
+    let _ = writeln!(res);
+    let _ = writeln!(res, "        ch = (ch << 6) | (p[i] & 0x3F);");
+    let _ = writeln!(res, "    }}");
+    let _ = writeln!(res);
+    let _ = writeln!(res, "    /* Overlong encodings, surrogates and values above U+10FFFF */");
+    let _ = writeln!(res, "    if ((length == 3 && ch < 0x800) || (length == 4 && (ch < 0x10000 || ch > 0x10FFFF)) || (ch >= 0xD800 && ch <= 0xDFFF)) {{");
+    let _ = writeln!(res, "        return 0xFFFFFFFF;");
+    let _ = writeln!(res, "    }}");
+    let _ = writeln!(res);
+    let _ = writeln!(res, "    *text = p + length;");
+    let _ = writeln!(res, "    return ch;");
+    let _ = writeln!(res, "}}");
+    let _ = writeln!(res);
+    let _ = writeln!(res, "bool {}(const char *text) {{", fn_name);
+
+    let initial_state = match dfa.initial_state.filter(|s| useful_states.contains(s)) {
+        Some(state) => state,
+        None        => {
+            let _ = writeln!(res, "    (void)text;");
+            let _ = writeln!(res, "    return false;");
+            let _ = writeln!(res, "}}");
+            return res;
+        }
+    };
+
+    let _ = writeln!(res, "    const unsigned char *p = (const unsigned char *)text;");
+    let _ = writeln!(res, "    uint32_t state = {};", initial_state);
+    let _ = writeln!(res);
+    let _ = writeln!(res, "    while (*p) {{");
+    let _ = writeln!(res, "        uint32_t ch = {}_next_char(&p);", fn_name);
+    let _ = writeln!(res);
+    let _ = writeln!(res, "        if (ch == 0xFFFFFFFF) {{");
+    let _ = writeln!(res, "            return false;");
+    let _ = writeln!(res, "        }}");
+    let _ = writeln!(res);
+    let _ = writeln!(res, "        switch (state) {{");
+
+    for (from, state_ranges) in &ranges {
+        if state_ranges.is_empty() {
+            continue;
+        }
+
+        let _ = writeln!(res, "        case {}:", from);
+
+        for (start, end, to) in state_ranges {
+            if start == end {
+                let _ = writeln!(res, "            if (ch == 0x{:X}) {{ state = {}; break; }}", *start as u32, to);
+            }
+            else {
+                let _ = writeln!(res, "            if (ch >= 0x{:X} && ch <= 0x{:X}) {{ state = {}; break; }}", *start as u32, *end as u32, to);
+            }
+        }
+
+        let _ = writeln!(res, "            return false;");
+    }
+
+    let _ = writeln!(res, "        default:");
+    let _ = writeln!(res, "            (void)ch;");
+    let _ = writeln!(res, "            return false;");
+    let _ = writeln!(res, "        }}");
+    let _ = writeln!(res, "    }}");
+    let _ = writeln!(res);
+
+    if final_states.is_empty() {
+        let _ = writeln!(res, "    return false;");
+    }
+    else {
+        let _ = writeln!(res, "    return {};", final_states.join(" || "));
+    }
+
+    let _ = writeln!(res, "}}");
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nfa::NFA;
+
+    fn build_dfa(string: &str) -> DFA {
+        DFA::from_nfa(NFA::from_string(string).unwrap()).minimize()
+    }
+
+    #[test]
+    fn transition_ranges_merge_consecutive_chars() {
+        let dfa = build_dfa("(a|b|c|e)x");
+        let ranges = transition_ranges(&dfa);
+        let initial_ranges = &ranges[&dfa.initial_state.unwrap()];
+
+        assert_eq!(initial_ranges.len(), 2);
+        assert_eq!((initial_ranges[0].0, initial_ranges[0].1), ('a', 'c'));
+        assert_eq!((initial_ranges[1].0, initial_ranges[1].1), ('e', 'e'));
+    }
+
+    #[test]
+    fn c_source() {
+        let dfa = build_dfa("ab*|é");
+        let source = to_c_source(&dfa, "is_match");
+        let function = &source[source.find("bool is_match").unwrap()..];
+
+        assert!(source.starts_with("#include <stdbool.h>\n#include <stdint.h>\n"));
+        assert!(source.contains("static uint32_t is_match_next_char(const unsigned char **text) {\n"));
+        assert_eq!(function, concat!(
+            "bool is_match(const char *text) {\n",
+            "    const unsigned char *p = (const unsigned char *)text;\n",
+            "    uint32_t state = 1;\n",
+            "\n",
+            "    while (*p) {\n",
+            "        uint32_t ch = is_match_next_char(&p);\n",
+            "\n",
+            "        if (ch == 0xFFFFFFFF) {\n",
+            "            return false;\n",
+            "        }\n",
+            "\n",
+            "        switch (state) {\n",
+            "        case 1:\n",
+            "            if (ch == 0x61) { state = 3; break; }\n",
+            "            if (ch == 0xE9) { state = 2; break; }\n",
+            "            return false;\n",
+            "        case 3:\n",
+            "            if (ch == 0x62) { state = 3; break; }\n",
+            "            return false;\n",
+            "        default:\n",
+            "            (void)ch;\n",
+            "            return false;\n",
+            "        }\n",
+            "    }\n",
+            "\n",
+            "    return state == 2 || state == 3;\n",
+            "}\n"
+        ));
+    }
+
+    #[test]
+    fn state_patterns() {
+        assert_eq!(super::state_patterns(vec![1, 3, 4, 5, 7, 8].into_iter()), vec!["1", "3..=5", "7..=8"]);
+    }
+
+    #[test]
+    fn rust_source_of_special_languages() {
+        assert_eq!(to_rust_source(&build_dfa(""), "never"), "pub fn never(_text: &str) -> bool {\n    false\n}\n");
+        assert_eq!(to_rust_source(&build_dfa("()?"), "empty"), "pub fn empty(text: &str) -> bool {\n    text.is_empty()\n}\n");
+    }
+
+    #[test]
+    fn rust_source() {
+        let dfa = build_dfa("ab*");
+
+        assert_eq!(to_rust_source(&dfa, "is_match"), concat!(
+            "pub fn is_match(text: &str) -> bool {\n",
+            "    let mut state: u32 = 1;\n",
+            "\n",
+            "    for ch in text.chars() {\n",
+            "        state = match (state, ch) {\n",
+            "            (1, 'a') => 2,\n",
+            "            (2, 'b') => 2,\n",
+            "            _ => return false\n",
+            "        };\n",
+            "    }\n",
+            "\n",
+            "    state == 2\n",
+            "}\n"
+        ));
+    }
+}
@@ -0,0 +1,312 @@
+use crate::dfa::DFA;
+use crate::expression::Expression;
+use crate::transition::Transition;
+
+use std::collections::{BTreeMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+// A word was inserted after a word that comes after it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderError {
+    pub previous: String,
+    pub word: String
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "words must be sorted, but {:?} comes after {:?}", self.word, self.previous)
+    }
+}
+
+impl Error for OrderError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Node {
+    is_final: bool,
+    // Sorted by the char, because the words are inserted in order
+    edges: Vec<(char, usize)>
+}
+
+// Builds a minimal acyclic automaton from words in sorted order with Daciuk's algorithm.
+// The path of the last inserted word is not minimized yet, because the next word may
+// still add edges to it. Once a word doesn't share a prefix with the path anymore,
+// the rest of the path is final and each of its nodes is replaced by an equivalent node
+// from the register or added to it. Two nodes are equivalent if they are both final
+// or not and have the same edges, so the automaton never gets larger than needed.
+#[derive(Debug)]
+pub struct DawgBuilder {
+    nodes: Vec<Node>,
+    register: BTreeMap<Node, usize>,
+    // The edges of the last word's path that are not minimized yet
+    unchecked: Vec<(usize, char, usize)>,
+    previous: Option<String>,
+    word_count: usize
+}
+
+impl DawgBuilder {
+    pub fn new() -> Self {
+        DawgBuilder {
+            nodes: vec![Node { is_final: false, edges: vec![] }],
+            register: BTreeMap::new(),
+            unchecked: vec![],
+            previous: None,
+            word_count: 0
+        }
+    }
+
+    // Adds a word, which must not come before the previously added word.
+    // Adding the same word again does nothing.
+    pub fn insert(&mut self, word: &str) -> Result<(), OrderError> {
+        let common_prefix = match &self.previous {
+            Some(previous) if word < previous.as_str() => {
+                return Err(OrderError { previous: previous.clone(), word: word.to_string() });
+            },
+            Some(previous) if word == previous => return Ok(()),
+            Some(previous) => previous.chars().zip(word.chars()).take_while(|(a, b)| a == b).count(),
+            None => 0
+        };
+
+        self.minimize(common_prefix);
+
+        let mut node = self.unchecked.last().map_or(0, |(_, _, child)| *child);
+
+        for letter in word.chars().skip(common_prefix) {
+            let child = self.nodes.len();
+            self.nodes.push(Node { is_final: false, edges: vec![] });
+            self.nodes[node].edges.push((letter, child));
+            self.unchecked.push((node, letter, child));
+            node = child;
+        }
+
+        self.nodes[node].is_final = true;
+        self.previous = Some(word.to_string());
+        self.word_count += 1;
+
+        Ok(())
+    }
+
+    // Replaces or registers the nodes of the unchecked path that are deeper than the depth
+    fn minimize(&mut self, depth: usize) {
+        while self.unchecked.len() > depth {
+            let (parent, letter, child) = self.unchecked.pop().expect("Checked by the loop condition");
+
+            match self.register.get(&self.nodes[child]) {
+                Some(equivalent) => {
+                    // The child was the last node added to the parent
+                    self.nodes[parent].edges.pop();
+                    self.nodes[parent].edges.push((letter, *equivalent));
+                },
+                None => {
+                    self.register.insert(self.nodes[child].clone(), child);
+                }
+            }
+        }
+    }
+
+    pub fn finish(mut self) -> Dawg {
+        self.minimize(0);
+
+        // Replaced nodes are still in the list, so only the reachable ones are numbered
+        let mut dfa = DFA::new();
+        let mut state_ids = BTreeMap::new();
+        let mut queue = VecDeque::new();
+
+        state_ids.insert(0, dfa.counter.tick());
+        queue.push_back(0);
+
+        while let Some(node) = queue.pop_front() {
+            let state = state_ids[&node];
+            dfa.states.insert(state);
+
+            if self.nodes[node].is_final {
+                dfa.final_states.insert(state);
+            }
+
+            for (letter, child) in &self.nodes[node].edges {
+                let child_state = match state_ids.get(child) {
+                    Some(child_state) => *child_state,
+                    None => {
+                        let child_state = dfa.counter.tick();
+                        state_ids.insert(*child, child_state);
+                        queue.push_back(*child);
+                        child_state
+                    }
+                };
+
+                dfa.alphabet.insert(*letter);
+                dfa.transitions.insert(Transition::new(state, *letter, child_state));
+            }
+        }
+
+        dfa.initial_state = Some(0);
+
+        Dawg {
+            dfa,
+            word_count: self.word_count
+        }
+    }
+}
+
+impl Default for DawgBuilder {
+    fn default() -> Self {
+        DawgBuilder::new()
+    }
+}
+
+// A set of words stored as a minimal acyclic DFA (directed acyclic word graph).
+// Words that share prefixes or suffixes share the states for them. States have
+// no transitions for chars that can't continue any word, so there is no dead state.
+#[derive(Debug)]
+pub struct Dawg {
+    dfa: DFA,
+    word_count: usize
+}
+
+impl Dawg {
+    // Builds the automaton out of words in sorted order
+    pub fn from_sorted<I: IntoIterator<Item = S>, S: AsRef<str>>(words: I) -> Result<Self, OrderError> {
+        let mut builder = DawgBuilder::new();
+
+        for word in words {
+            builder.insert(word.as_ref())?;
+        }
+
+        Ok(builder.finish())
+    }
+
+    pub fn len(&self) -> usize {
+        self.word_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.word_count == 0
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.dfa.matches(word)
+    }
+
+    // Returns whether any word starts with the prefix
+    pub fn has_prefix(&self, prefix: &str) -> bool {
+        // Every state leads to a word, except for the initial state when there are no words
+        !self.is_empty() && self.state_after(prefix).is_some()
+    }
+
+    // Returns the words in sorted order
+    pub fn words(&self) -> Words<'_> {
+        self.words_with_prefix("")
+    }
+
+    // Returns the words that start with the prefix in sorted order
+    pub fn words_with_prefix(&self, prefix: &str) -> Words<'_> {
+        Words {
+            dfa: &self.dfa,
+            stack: self.state_after(prefix).map(|state| (prefix.to_string(), state)).into_iter().collect()
+        }
+    }
+
+    fn state_after(&self, prefix: &str) -> Option<u32> {
+        prefix.chars().try_fold(self.dfa.initial_state?, |state, ch| self.dfa.reachable(state, ch))
+    }
+
+    pub fn dfa(&self) -> &DFA {
+        &self.dfa
+    }
+
+    // Returns an expression that matches exactly the words
+    pub fn into_expression(self) -> Expression {
+        Expression::from_dfa(self.dfa)
+    }
+}
+
+// Walks the automaton depth first. The prefixes that are still to be visited are kept
+// on a stack with the smallest one on top, so each word comes before its continuations.
+pub struct Words<'a> {
+    dfa: &'a DFA,
+    stack: Vec<(String, u32)>
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        while let Some((prefix, state)) = self.stack.pop() {
+            for transition in self.dfa.transitions_from(state).collect::<Vec<_>>().into_iter().rev() {
+                let mut word = prefix.clone();
+                word.push(transition.label);
+                self.stack.push((word, transition.to));
+            }
+
+            if self.dfa.final_states.contains(&state) {
+                return Some(prefix);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_prefixes_and_suffixes() {
+        let dawg = Dawg::from_sorted(["tap", "taps", "top", "tops"]).unwrap();
+
+        // t -> a|o -> p -> (final) -> s -> (final)
+        assert_eq!(dawg.dfa.states.len(), 5);
+        assert_eq!(dawg.dfa.transitions.len(), 5);
+        assert_eq!(dawg.dfa.final_states.len(), 2);
+        assert_eq!(dawg.len(), 4);
+    }
+
+    #[test]
+    fn minimal_like_minimizer() {
+        let words = ["abc", "abd", "acd", "bc", "bcd", "bd", "c"];
+        let dawg = Dawg::from_sorted(words).unwrap();
+        let minimized = Expression::new(&words.join("|"));
+
+        // The minimizer also keeps a dead state
+        assert_eq!(dawg.dfa.states.len() + 1, minimized.dfa().states.len());
+    }
+
+    #[test]
+    fn builder_register_replaces_nodes() {
+        let mut builder = DawgBuilder::new();
+
+        builder.insert("ab").unwrap();
+        builder.insert("cb").unwrap();
+        builder.minimize(0);
+
+        // The nodes of "cb" were replaced by the equivalent nodes of "ab"
+        assert_eq!(builder.nodes[0].edges, vec![('a', 1), ('c', 1)]);
+        assert_eq!(builder.nodes[1].edges, vec![('b', 2)]);
+    }
+
+    #[test]
+    fn order_errors() {
+        let mut builder = DawgBuilder::new();
+
+        builder.insert("b").unwrap();
+        builder.insert("b").unwrap();
+
+        assert_eq!(builder.insert("a"), Err(OrderError { previous: "b".to_string(), word: "a".to_string() }));
+        assert_eq!(builder.finish().len(), 1);
+    }
+
+    #[test]
+    fn empty() {
+        let dawg = Dawg::from_sorted(Vec::<String>::new()).unwrap();
+
+        assert!(dawg.is_empty());
+        assert!(!dawg.contains(""));
+        assert!(!dawg.has_prefix(""));
+        assert_eq!(dawg.words().count(), 0);
+
+        let dawg = Dawg::from_sorted([""]).unwrap();
+        assert!(dawg.contains(""));
+        assert_eq!(dawg.words().collect::<Vec<_>>(), vec![""]);
+    }
+}
@@ -7,14 +7,18 @@ use std::collections::{BTreeSet, BTreeMap};
 
 pub(crate) struct Determinizer {
     nfa: NFA,
-    dfa: DFA
+    dfa: DFA,
+
+    // The set of NFA states that each DFA state stands for
+    state_subsets: BTreeMap<u32, BTreeSet<u32>>
 }    
 
 impl Determinizer {
     pub(crate) fn new(new_nfa: NFA) -> Self {
         Determinizer {
             nfa: new_nfa,
-            dfa: DFA::new()
+            dfa: DFA::new(),
+            state_subsets: BTreeMap::new()
         }
     }
 
@@ -75,6 +79,7 @@ impl Determinizer {
         dfa.counter = set_states_counter;
 
         self.dfa = dfa;
+        self.state_subsets = found_set_states.into_iter().map(|(subset, state)| (state, subset)).collect();
         self
     }
 
@@ -115,6 +120,11 @@ impl Determinizer {
     pub(crate) fn take(self) -> DFA {
         self.dfa
     }
+
+    // Same as take, but also returns the set of NFA states that each DFA state stands for
+    pub(crate) fn take_with_subsets(self) -> (DFA, BTreeMap<u32, BTreeSet<u32>>) {
+        (self.dfa, self.state_subsets)
+    }
 }
 
 #[cfg(test)]
@@ -176,4 +186,27 @@ mod tests {
             Transition::new(2, 'b', 2)
         ]);
     }
+
+    #[test]
+    fn determinize_with_subsets() {
+        let mut nfa = NFA::new();
+
+        nfa.alphabet = set!['a'];
+        nfa.states = set![0, 1, 2];
+        nfa.counter.value = 3;
+
+        nfa.initial_states = set![0];
+        nfa.final_states = set![2];
+
+        nfa.transitions = set![
+            Transition::new(0, Some('a'), 1),
+            Transition::new(1, None, 2)
+        ];
+
+        let (dfa, subsets) = Determinizer::new(nfa).determinize().take_with_subsets();
+
+        assert_eq!(dfa.states, set![0, 1, 2]);
+        assert_eq!(dfa.final_states, set![1]);
+        assert_eq!(subsets, vec![(0, set![0]), (1, set![1, 2]), (2, BTreeSet::new())].into_iter().collect());
+    }
 }
@@ -1,10 +1,27 @@
 use crate::transition::Transition;
 use crate::counter::Counter;
+use crate::elimination::StateEliminator;
+use crate::dot::DotBuilder;
+use crate::nfa::NFA;
+use crate::determinizer::Determinizer;
+use crate::minimizer::Minimizer;
+use crate::binary;
+use crate::codegen;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, BTreeMap, VecDeque};
+use std::fmt;
 
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+pub use crate::binary::{DfaRef, FormatError};
+pub use crate::table::{DfaTable, StaticDfa};
+
+// When deserializing, the automaton is first read into DFAData and then validated
 #[derive(Debug)]
-pub(crate) struct DFA {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "crate::serialization::DFAData"))]
+pub struct DFA {
     pub(crate) alphabet: BTreeSet<char>,
     pub(crate) states: BTreeSet<u32>,
     pub(crate) transitions: BTreeSet<Transition<char>>,
@@ -12,6 +29,7 @@ pub(crate) struct DFA {
     pub(crate) initial_state: Option<u32>,
 
     // Counter to track what the next state's id will be
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) counter: Counter
 }
 
@@ -27,19 +45,347 @@ impl DFA {
         }
     }
 
+    // Builds a DFA that matches the same strings as the NFA by the subset construction
+    pub fn from_nfa(nfa: NFA) -> Self {
+        Determinizer::new(nfa).determinize().take()
+    }
+
+    pub fn minimize(mut self) -> Self {
+        self.complete();
+        Minimizer::new(self).minimize().take()
+    }
+
+    // Adds a dead state for the chars that some states have no transition with,
+    // so that every state has a transition with every char of the alphabet
+    pub(crate) fn complete(&mut self) {
+        let mut missing = vec![];
+
+        for state in &self.states {
+            for letter in &self.alphabet {
+                if self.reachable(*state, *letter).is_none() {
+                    missing.push((*state, *letter));
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            return;
+        }
+
+        let dead_state = self.counter.tick();
+        self.states.insert(dead_state);
+
+        for letter in &self.alphabet {
+            self.transitions.insert(Transition::new(dead_state, *letter, dead_state));
+        }
+
+        for (state, letter) in missing {
+            self.transitions.insert(Transition::new(state, letter, dead_state));
+        }
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn transition_count(&self) -> usize {
+        self.transitions.len()
+    }
+
+    pub fn initial_state(&self) -> Option<u32> {
+        self.initial_state
+    }
+
+    pub fn is_final(&self, state: u32) -> bool {
+        self.final_states.contains(&state)
+    }
+
+    // Returns the state that the automaton moves to from the given state with the symbol
+    // or None if there is no such transition, e.g. if the symbol is not in the alphabet
+    pub fn next_state(&self, state: u32, symbol: char) -> Option<u32> {
+        self.reachable(state, symbol)
+    }
+
+    // Returns the states from which no final state can be reached. Once the automaton
+    // enters one of them, it can never accept.
+    pub fn dead_states(&self) -> BTreeSet<u32> {
+        self.states.difference(&self.live_states()).cloned().collect()
+    }
+
     // Returns the states that are reachable by a state
     // through a specific transition
     pub(crate) fn reachable(&self, start_state: u32, wanted_label: char) -> Option<u32> {
+        let start = Transition::new(start_state, wanted_label, 0);
+        let end = Transition::new(start_state, wanted_label, u32::MAX);
+
+        self.transitions.range(start..=end).next().map(|t| t.to)
+    }
+
+    // Returns the transitions going out of a state, ordered by their label
+    pub(crate) fn transitions_from(&self, state: u32) -> impl Iterator<Item = &Transition<char>> {
+        let start = Transition::new(state, '\0', 0);
+        let end = Transition::new(state, char::MAX, u32::MAX);
+
+        self.transitions.range(start..=end)
+    }
+
+    // Returns the states from which a final state can be reached. All other
+    // states are dead, because once the automaton enters them, it can never accept.
+    pub(crate) fn live_states(&self) -> BTreeSet<u32> {
+        let mut res = self.final_states.clone();
+        let mut found_this_step = self.final_states.clone();
+        let mut found_last_step: BTreeSet<u32>;
+
+        while !found_this_step.is_empty() {
+            found_last_step = found_this_step;
+            found_this_step = BTreeSet::new();
+
+            for transition in &self.transitions {
+                if found_last_step.contains(&transition.to) && !res.contains(&transition.from) {
+                    res.insert(transition.from);
+                    found_this_step.insert(transition.from);
+                }
+            }
+        }
+
+        res
+    }
+
+    // Returns the states that can be reached from the initial state and from which
+    // a final state can be reached. Only these states matter for the accepted strings.
+    pub(crate) fn useful_states(&self) -> BTreeSet<u32> {
+        let live_states = self.live_states();
+        let mut res = BTreeSet::new();
+        let mut found_this_step = BTreeSet::new();
+        let mut found_last_step: BTreeSet<u32>;
+
+        if let Some(initial_state) = self.initial_state {
+            if live_states.contains(&initial_state) {
+                res.insert(initial_state);
+                found_this_step.insert(initial_state);
+            }
+        }
+
+        while !found_this_step.is_empty() {
+            found_last_step = found_this_step;
+            found_this_step = BTreeSet::new();
+
+            for state in found_last_step {
+                for transition in self.transitions_from(state) {
+                    if live_states.contains(&transition.to) && !res.contains(&transition.to) {
+                        res.insert(transition.to);
+                        found_this_step.insert(transition.to);
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    // Returns how many strings of the given length the automaton accepts or None
+    // if the count doesn't fit into an u128
+    pub(crate) fn count_of_length(&self, length: usize) -> Option<u128> {
+        let mut counts = PathCounts::new(self);
+
+        for _ in 0..length {
+            if !counts.advance() {
+                break;
+            }
+        }
+
+        counts.accepted()
+    }
+
+    // Returns how many strings of length up to max_length the automaton accepts or
+    // None if the count doesn't fit into an u128
+    pub(crate) fn count_up_to(&self, max_length: usize) -> Option<u128> {
+        let mut counts = PathCounts::new(self);
+        let mut sum = counts.accepted()?;
+
+        for length in 1..=max_length {
+            if !counts.advance() {
+                // The lengths from here to max_length all have the same count
+                let remaining = (max_length - length) as u128 + 1;
+                return sum.checked_add(counts.accepted()?.checked_mul(remaining)?);
+            }
+
+            sum = sum.checked_add(counts.accepted()?)?;
+        }
+
+        Some(sum)
+    }
+
+    // Builds a regular expression that matches the same strings as the automaton
+    pub(crate) fn to_expression_string(&self) -> String {
+        StateEliminator::new(self).eliminate().to_string()
+    }
+
+    // Returns a Graphviz DOT description of the automaton. If hide_dead_states is set,
+    // the states from which a final state can't be reached are left out, except
+    // for the initial state.
+    pub fn to_dot(&self, hide_dead_states: bool) -> String {
+        let live_states = self.live_states();
+        let is_shown = |state: &u32| !hide_dead_states || live_states.contains(state) || self.initial_state == Some(*state);
+        let mut builder = DotBuilder::new("DFA");
+
+        for state in self.states.iter().filter(|s| is_shown(s)) {
+            builder.state(*state, self.initial_state == Some(*state), self.final_states.contains(state));
+        }
+
         for transition in &self.transitions {
-            if transition.from == start_state && transition.label == wanted_label {
-                return Some(transition.to);
+            if is_shown(&transition.from) && is_shown(&transition.to) {
+                builder.transition(transition.from, Some(transition.label), transition.to);
+            }
+        }
+
+        builder.build()
+    }
+
+    // Returns the automaton as a dense transition table
+    pub fn to_table(&self) -> DfaTable {
+        DfaTable::from_dfa(self)
+    }
+
+    // Returns whether any substring of the text is accepted. All positions of the text
+    // are tried at once by following the set of states that the substrings
+    // ending at the current position lead to. The live states are passed in, so that
+    // they are computed once for all the texts that are searched.
+    pub(crate) fn matches_substring(&self, text: &str, live_states: &BTreeSet<u32>) -> bool {
+        let initial_state = match self.initial_state {
+            Some(state) if live_states.contains(&state) => state,
+            _ => return false
+        };
+
+        let mut current_states: BTreeSet<u32> = set![initial_state];
+
+        for ch in text.chars() {
+            if !current_states.is_disjoint(&self.final_states) {
+                return true;
+            }
+
+            current_states = current_states.iter()
+                                           .filter_map(|state| self.reachable(*state, ch))
+                                           .filter(|state| live_states.contains(state))
+                                           .collect();
+            current_states.insert(initial_state);
+        }
+
+        !current_states.is_disjoint(&self.final_states)
+    }
+
+    // Builds an automaton that accepts the strings accepted by both automata. It runs
+    // both of them side by side, so its states are the reachable pairs of their states.
+    // Only the chars in both alphabets can appear in such strings.
+    pub(crate) fn intersection(&self, other: &DFA) -> DFA {
+        let mut res = DFA::new();
+        res.alphabet = self.alphabet.intersection(&other.alphabet).cloned().collect();
+
+        let (first, second) = match (self.initial_state, other.initial_state) {
+            (Some(first), Some(second)) => (first, second),
+            _ => return res
+        };
+
+        let mut pair_states: BTreeMap<(u32, u32), u32> = BTreeMap::new();
+        let mut queue = VecDeque::new();
+
+        pair_states.insert((first, second), res.counter.tick());
+        queue.push_back((first, second));
+
+        while let Some(pair) = queue.pop_front() {
+            let state = pair_states[&pair];
+
+            if self.final_states.contains(&pair.0) && other.final_states.contains(&pair.1) {
+                res.final_states.insert(state);
+            }
+
+            for letter in &res.alphabet {
+                let next = match (self.reachable(pair.0, *letter), other.reachable(pair.1, *letter)) {
+                    (Some(first), Some(second)) => (first, second),
+                    _ => continue
+                };
+
+                let next_state = match pair_states.get(&next) {
+                    Some(next_state) => *next_state,
+                    None => {
+                        let next_state = res.counter.tick();
+                        pair_states.insert(next, next_state);
+                        queue.push_back(next);
+                        next_state
+                    }
+                };
+
+                res.transitions.insert(Transition::new(state, *letter, next_state));
+            }
+        }
+
+        res.states = pair_states.values().cloned().collect();
+        res.initial_state = Some(0);
+        res
+    }
+
+    // Returns the shortest string that is accepted by exactly one of the automata
+    // or None if they accept the same strings. Both automata are run side by side,
+    // with None standing for an automaton that has no transition left to take.
+    pub(crate) fn distinguishing_string(&self, other: &DFA) -> Option<String> {
+        let alphabet: BTreeSet<char> = self.alphabet.union(&other.alphabet).cloned().collect();
+        let is_final = |dfa: &DFA, state: Option<u32>| state.is_some_and(|state| dfa.final_states.contains(&state));
+        let step = |dfa: &DFA, state: Option<u32>, letter: char| state.and_then(|state| dfa.reachable(state, letter));
+
+        type StatePair = (Option<u32>, Option<u32>);
+
+        let start = (self.initial_state, other.initial_state);
+        let mut found: BTreeMap<StatePair, (StatePair, char)> = BTreeMap::new();
+        let mut visited: BTreeSet<StatePair> = set![start];
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+
+        while let Some(pair) = queue.pop_front() {
+            if is_final(self, pair.0) != is_final(other, pair.1) {
+                // Walk back through the pairs that led here to rebuild the string
+                let mut res = vec![];
+                let mut current = pair;
+
+                while let Some(&(previous, letter)) = found.get(&current) {
+                    res.push(letter);
+                    current = previous;
+                }
+
+                return Some(res.into_iter().rev().collect());
+            }
+
+            for letter in &alphabet {
+                let next = (step(self, pair.0, *letter), step(other, pair.1, *letter));
+
+                if next != (None, None) && visited.insert(next) {
+                    found.insert(next, (pair, *letter));
+                    queue.push_back(next);
+                }
             }
         }
 
         None
     }
 
-    pub(crate) fn matches(&self, text: &str) -> bool {
+    // Generates the source of a standalone Rust function with the given name that
+    // matches the same strings as the automaton. It can be used from a build script.
+    pub fn to_rust_source(&self, fn_name: &str) -> String {
+        codegen::to_rust_source(self, fn_name)
+    }
+
+    // Generates the source of a self-contained C function with the given name that
+    // matches the same nul-terminated UTF-8 strings as the automaton
+    pub fn to_c_source(&self, fn_name: &str) -> String {
+        codegen::to_c_source(self, fn_name)
+    }
+
+    // Encodes the automaton in a compact binary format that can be matched
+    // against directly with DfaRef::from_bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        binary::to_bytes(self)
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
         let mut current_state;
 
         match self.initial_state {
@@ -57,3 +403,378 @@ impl DFA {
         self.final_states.contains(&current_state)
     }
 }
+
+// Prints the transition table with a row for each state and a column for each
+// char of the alphabet. The initial state is marked with "->" and final states with "*".
+impl fmt::Display for DFA {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rows = vec![];
+        let mut header = vec![String::new(), String::new()];
+        header.extend(self.alphabet.iter().map(|letter| letter.to_string()));
+        rows.push(header);
+
+        for state in &self.states {
+            let mut marker = String::new();
+
+            if self.initial_state == Some(*state) {
+                marker.push_str("->");
+            }
+
+            if self.final_states.contains(state) {
+                marker.push('*');
+            }
+
+            let mut row = vec![marker, state.to_string()];
+
+            for letter in &self.alphabet {
+                row.push(self.reachable(*state, *letter).map_or("-".to_string(), |to| to.to_string()));
+            }
+
+            rows.push(row);
+        }
+
+        write_table(f, &rows)
+    }
+}
+
+// Writes the rows with each column right-aligned to its widest cell
+pub(crate) fn write_table(f: &mut fmt::Formatter, rows: &[Vec<String>]) -> fmt::Result {
+    let column_count = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..column_count).map(|column| {
+        rows.iter().filter_map(|row| row.get(column)).map(|cell| cell.chars().count()).max().unwrap_or(0)
+    }).collect();
+
+    for row in rows {
+        let cells: Vec<String> = row.iter().zip(&widths).map(|(cell, width)| format!("{:>width$}", cell, width = width)).collect();
+        writeln!(f, "{}", cells.join(" ").trim_end())?;
+    }
+
+    Ok(())
+}
+
+// The number of different strings of the current length that lead from the initial
+// state to each state, starting with length 0. Only the current length is kept, so
+// counting up to a length takes memory for the states only. Dead states are left out,
+// because the strings that reach them can't be accepted and would only cause overflows.
+struct PathCounts<'a> {
+    dfa: &'a DFA,
+    live_states: BTreeSet<u32>,
+    // The counts of the states, where None is a count that doesn't fit into an u128.
+    // A state can overflow while the final states still have small counts.
+    paths: BTreeMap<u32, Option<u128>>
+}
+
+impl<'a> PathCounts<'a> {
+    fn new(dfa: &'a DFA) -> Self {
+        let live_states = dfa.live_states();
+        let paths = dfa.initial_state.filter(|state| live_states.contains(state)).map(|state| (state, Some(1))).into_iter().collect();
+
+        PathCounts {
+            dfa,
+            live_states,
+            paths
+        }
+    }
+
+    fn accepted(&self) -> Option<u128> {
+        self.paths
+            .iter()
+            .filter(|(state, _)| self.dfa.final_states.contains(state))
+            .try_fold(0u128, |sum, (_, count)| sum.checked_add((*count)?))
+    }
+
+    // Moves on to the next length. Returns false if the counts didn't change, because
+    // then they stay the same for all longer strings and counting can stop. A count that
+    // overflowed stays None, but so do the counts of all the states it leads to, so
+    // the final states' counts are still right when the others stop changing.
+    fn advance(&mut self) -> bool {
+        let mut next_paths = BTreeMap::<u32, Option<u128>>::new();
+
+        for (state, count) in &self.paths {
+            for transition in self.dfa.transitions_from(*state) {
+                if self.live_states.contains(&transition.to) {
+                    let next_count = next_paths.entry(transition.to).or_insert(Some(0));
+                    *next_count = next_count.zip(*count).and_then(|(next_count, count)| next_count.checked_add(count));
+                }
+            }
+        }
+
+        if self.paths == next_paths {
+            return false;
+        }
+
+        self.paths = next_paths;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn live_states() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a'];
+        dfa.states = set![0, 1, 2, 3];
+        dfa.counter.value = 4;
+
+        dfa.initial_state = Some(1);
+        dfa.final_states = set![3];
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 0),
+            Transition::new(1, 'a', 2),
+            Transition::new(2, 'a', 3),
+            Transition::new(3, 'a', 0)
+        ];
+
+        assert_eq!(dfa.live_states(), set![1, 2, 3]);
+    }
+
+    #[test]
+    fn transitions_from() {
+        let mut dfa = DFA::new();
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 1),
+            Transition::new(1, 'b', 0),
+            Transition::new(1, 'a', 1),
+            Transition::new(2, 'a', 0)
+        ];
+
+        let transitions: Vec<Transition<char>> = dfa.transitions_from(1).cloned().collect();
+        assert_eq!(transitions, vec![Transition::new(1, 'a', 1), Transition::new(1, 'b', 0)]);
+    }
+
+    #[test]
+    fn matches_substring() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0, 1, 2, 3];
+        dfa.counter.value = 4;
+
+        dfa.initial_state = Some(1);
+        dfa.final_states = set![3];
+
+        // Accepts only "ab"
+        dfa.transitions = set![
+            Transition::new(0, 'a', 0),
+            Transition::new(0, 'b', 0),
+            Transition::new(1, 'a', 2),
+            Transition::new(1, 'b', 0),
+            Transition::new(2, 'a', 0),
+            Transition::new(2, 'b', 3),
+            Transition::new(3, 'a', 0),
+            Transition::new(3, 'b', 0)
+        ];
+
+        let live_states = dfa.live_states();
+        assert!(dfa.matches_substring("ab", &live_states));
+        assert!(dfa.matches_substring("xxaab", &live_states));
+        assert!(dfa.matches_substring("abba", &live_states));
+        assert!(!dfa.matches_substring("ba", &live_states));
+        assert!(!dfa.matches_substring("axb", &live_states));
+        assert!(!dfa.matches_substring("", &live_states));
+    }
+
+    #[test]
+    fn display_table() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0, 1, 12];
+        dfa.counter.value = 13;
+
+        dfa.initial_state = Some(1);
+        dfa.final_states = set![1, 12];
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 0),
+            Transition::new(0, 'b', 0),
+            Transition::new(1, 'a', 12),
+            Transition::new(1, 'b', 0),
+            Transition::new(12, 'a', 0)
+        ];
+
+        assert_eq!(dfa.to_string(), concat!(
+            "        a b\n",
+            "     0  0 0\n",
+            "->*  1 12 0\n",
+            "  * 12  0 -\n"
+        ));
+    }
+
+    #[test]
+    fn to_dot() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0, 1];
+        dfa.counter.value = 2;
+
+        dfa.initial_state = Some(1);
+        dfa.final_states = set![1];
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 0),
+            Transition::new(0, 'b', 0),
+            Transition::new(1, 'a', 1),
+            Transition::new(1, 'b', 0)
+        ];
+
+        let dot = dfa.to_dot(false);
+        assert!(dot.contains("    0 -> 0 [label = \"a, b\"];\n"));
+        assert!(dot.contains("    1 [shape = doublecircle];\n"));
+        assert!(dot.contains("    start -> 1;\n"));
+
+        let dot = dfa.to_dot(true);
+        assert!(!dot.contains("0 [shape = circle]"));
+        assert!(!dot.contains("-> 0"));
+        assert!(dot.contains("    1 -> 1 [label = \"a\"];\n"));
+    }
+
+    #[test]
+    fn count_by_length() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0, 1];
+        dfa.counter.value = 2;
+
+        dfa.initial_state = Some(0);
+        dfa.final_states = set![1];
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 1),
+            Transition::new(0, 'b', 0),
+            Transition::new(1, 'a', 1),
+            Transition::new(1, 'b', 0)
+        ];
+
+        // Strings over {a, b} that end with 'a'
+        let counts: Vec<Option<u128>> = (0..=3).map(|length| dfa.count_of_length(length)).collect();
+        assert_eq!(counts, vec![Some(0), Some(1), Some(2), Some(4)]);
+        assert_eq!(dfa.count_up_to(3), Some(7));
+    }
+
+    #[test]
+    fn count_by_length_overflow() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0];
+        dfa.counter.value = 1;
+
+        dfa.initial_state = Some(0);
+        dfa.final_states = set![0];
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 0),
+            Transition::new(0, 'b', 0)
+        ];
+
+        assert_eq!(dfa.count_of_length(127), Some(1 << 127));
+        assert_eq!(dfa.count_of_length(128), None);
+        assert_eq!(dfa.count_of_length(130), None);
+        assert_eq!(dfa.count_up_to(126), Some((1 << 127) - 1));
+        assert_eq!(dfa.count_up_to(127), Some(u128::MAX));
+        assert_eq!(dfa.count_up_to(128), None);
+    }
+
+    #[test]
+    fn dead_states() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0, 1, 2];
+        dfa.counter.value = 3;
+
+        dfa.initial_state = Some(1);
+        dfa.final_states = set![2];
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 0),
+            Transition::new(0, 'b', 0),
+            Transition::new(1, 'a', 2),
+            Transition::new(1, 'b', 0),
+            Transition::new(2, 'a', 0),
+            Transition::new(2, 'b', 0)
+        ];
+
+        assert_eq!(dfa.dead_states(), set![0]);
+        assert_eq!(dfa.next_state(1, 'a'), Some(2));
+        assert_eq!(dfa.next_state(1, 'c'), None);
+        assert!(dfa.is_final(2));
+    }
+
+    #[test]
+    fn distinguishing_string() {
+        let first = DFA::from_nfa(NFA::from_string("ab*").unwrap()).minimize();
+        let second = DFA::from_nfa(NFA::from_string("a|ab|abbb").unwrap());
+        let third = DFA::from_nfa(NFA::from_string("ab*|a(bb)*").unwrap());
+
+        assert_eq!(first.distinguishing_string(&second), Some("abb".to_string()));
+        assert_eq!(first.distinguishing_string(&third), None);
+        assert_eq!(third.distinguishing_string(&first), None);
+    }
+
+    #[test]
+    fn distinguishing_string_with_different_alphabets() {
+        let first = DFA::from_nfa(NFA::from_string("a*").unwrap());
+        let second = DFA::from_nfa(NFA::from_string("a*|b").unwrap());
+        let third = DFA::from_nfa(NFA::from_string("(a|b)*").unwrap());
+
+        assert_eq!(first.distinguishing_string(&second), Some("b".to_string()));
+        assert_eq!(second.distinguishing_string(&third), Some("ab".to_string()));
+    }
+
+    #[test]
+    fn intersection() {
+        let first = DFA::from_nfa(NFA::from_string("(a|b)*a").unwrap()).minimize();
+        let second = DFA::from_nfa(NFA::from_string("(a|c)(a|c)").unwrap()).minimize();
+        let intersection = first.intersection(&second);
+
+        assert_eq!(intersection.alphabet, set!['a']);
+        assert_eq!(intersection.initial_state, Some(0));
+        assert!(intersection.matches("aa"));
+        assert!(!intersection.matches("a"));
+        assert!(!intersection.matches("aaa"));
+        assert!(!intersection.matches("ca"));
+        assert_eq!(intersection.minimize().distinguishing_string(&DFA::from_nfa(NFA::from_string("aa").unwrap())), None);
+    }
+
+    #[test]
+    fn complete() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0, 1];
+        dfa.counter.value = 2;
+
+        dfa.initial_state = Some(0);
+        dfa.final_states = set![1];
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 1)
+        ];
+
+        dfa.complete();
+
+        assert_eq!(dfa.states, set![0, 1, 2]);
+        assert_eq!(dfa.transitions, set![
+            Transition::new(0, 'a', 1),
+            Transition::new(0, 'b', 2),
+            Transition::new(1, 'a', 2),
+            Transition::new(1, 'b', 2),
+            Transition::new(2, 'a', 2),
+            Transition::new(2, 'b', 2)
+        ]);
+
+        let minimized = dfa.minimize();
+        assert!(minimized.matches("a"));
+        assert!(!minimized.matches("ab"));
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::{BTreeSet, BTreeMap};
+use std::fmt::Write;
+
+// Builds a Graphviz DOT description of an automaton. The labels of all transitions
+// between the same two states are merged into a single edge. A None label is an
+// epsilon transition.
+pub(crate) struct DotBuilder<'a> {
+    name: &'a str,
+    states: BTreeSet<u32>,
+    initial_states: BTreeSet<u32>,
+    final_states: BTreeSet<u32>,
+    edges: BTreeMap<(u32, u32), BTreeSet<Option<char>>>
+}
+
+impl<'a> DotBuilder<'a> {
+    pub(crate) fn new(name: &'a str) -> Self {
+        DotBuilder {
+            name,
+            states: BTreeSet::new(),
+            initial_states: BTreeSet::new(),
+            final_states: BTreeSet::new(),
+            edges: BTreeMap::new()
+        }
+    }
+
+    pub(crate) fn state(&mut self, state: u32, is_initial: bool, is_final: bool) {
+        self.states.insert(state);
+
+        if is_initial {
+            self.initial_states.insert(state);
+        }
+
+        if is_final {
+            self.final_states.insert(state);
+        }
+    }
+
+    pub(crate) fn transition(&mut self, from: u32, label: Option<char>, to: u32) {
+        self.edges.entry((from, to)).or_default().insert(label);
+    }
+
+    pub(crate) fn build(&self) -> String {
+        let mut res = String::new();
+
+        // Writing into a String can't fail, so the results are ignored
+        let _ = writeln!(res, "digraph {} {{", self.name);
+        let _ = writeln!(res, "    rankdir = LR;");
+        let _ = writeln!(res, "    start [shape = point];");
+
+        for state in &self.states {
+            let shape = if self.final_states.contains(state) { "doublecircle" } else { "circle" };
+            let _ = writeln!(res, "    {} [shape = {}];", state, shape);
+        }
+
+        for state in &self.initial_states {
+            let _ = writeln!(res, "    start -> {};", state);
+        }
+
+        for ((from, to), labels) in &self.edges {
+            let _ = writeln!(res, "    {} -> {} [label = \"{}\"];", from, to, DotBuilder::label(labels));
+        }
+
+        res.push_str("}\n");
+        res
+    }
+
+    // Joins the labels, turning runs of at least three consecutive chars into
+    // ranges like "a-z" and epsilon into "ε"
+    fn label(labels: &BTreeSet<Option<char>>) -> String {
+        let chars: Vec<char> = labels.iter().filter_map(|label| *label).collect();
+        let mut parts = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let mut j = i;
+
+            while j + 1 < chars.len() && (chars[j] as u32) + 1 == chars[j + 1] as u32 {
+                j += 1;
+            }
+
+            if j - i >= 2 {
+                parts.push(format!("{}-{}", DotBuilder::escape(chars[i]), DotBuilder::escape(chars[j])));
+                i = j + 1;
+            }
+            else {
+                parts.push(DotBuilder::escape(chars[i]));
+                i += 1;
+            }
+        }
+
+        if labels.contains(&None) {
+            parts.push("ε".to_string());
+        }
+
+        parts.join(", ")
+    }
+
+    fn escape(letter: char) -> String {
+        match letter {
+            '"' | '\\' => format!("\\{}", letter),
+            _ if letter.is_control() => letter.escape_default().collect::<String>().replace('\\', "\\\\"),
+            _ => letter.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_ranges() {
+        let labels = set![Some('a'), Some('b'), Some('c'), Some('e'), Some('x'), Some('y'), None];
+        assert_eq!(DotBuilder::label(&labels), "a-c, e, x, y, ε");
+    }
+
+    #[test]
+    fn label_escapes() {
+        let labels = set![Some('"'), Some('\\'), Some('\n')];
+        assert_eq!(DotBuilder::label(&labels), "\\\\n, \\\", \\\\");
+    }
+
+    #[test]
+    fn build() {
+        let mut builder = DotBuilder::new("DFA");
+
+        builder.state(0, true, false);
+        builder.state(1, false, true);
+        builder.transition(0, Some('a'), 1);
+        builder.transition(0, Some('b'), 1);
+        builder.transition(1, None, 0);
+
+        assert_eq!(builder.build(), concat!(
+            "digraph DFA {\n",
+            "    rankdir = LR;\n",
+            "    start [shape = point];\n",
+            "    0 [shape = circle];\n",
+            "    1 [shape = doublecircle];\n",
+            "    start -> 0;\n",
+            "    0 -> 1 [label = \"a, b\"];\n",
+            "    1 -> 0 [label = \"ε\"];\n",
+            "}\n"
+        ));
+    }
+}
@@ -0,0 +1,388 @@
+use crate::dfa::DFA;
+use crate::parser::SPECIAL_CHARS;
+
+use std::collections::{BTreeSet, BTreeMap};
+use std::fmt;
+
+// A regular expression tree. The constructor functions simplify the
+// expressions as they are built, so that the final expression stays readable.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Pattern {
+    // Matches nothing
+    Nothing,
+
+    // Matches only the empty string
+    Empty,
+    Char(char),
+    Concat(Vec<Pattern>),
+
+    // The alternatives are kept sorted and without duplicates
+    Union(Vec<Pattern>),
+    Optional(Box<Pattern>),
+    Kleene(Box<Pattern>),
+    Plus(Box<Pattern>)
+}
+
+impl Pattern {
+    fn matches_empty(&self) -> bool {
+        match self {
+            Pattern::Nothing | Pattern::Char(_) => false,
+            Pattern::Empty | Pattern::Optional(_) | Pattern::Kleene(_) => true,
+            Pattern::Plus(inner) => inner.matches_empty(),
+            Pattern::Concat(parts) => parts.iter().all(Pattern::matches_empty),
+            Pattern::Union(parts) => parts.iter().any(Pattern::matches_empty)
+        }
+    }
+
+    // Collects the alternatives of nested unions into a single set
+    fn collect_alternatives(self, alternatives: &mut BTreeSet<Pattern>, has_empty: &mut bool) {
+        match self {
+            Pattern::Nothing => {},
+            Pattern::Empty => *has_empty = true,
+            Pattern::Optional(inner) => {
+                *has_empty = true;
+                inner.collect_alternatives(alternatives, has_empty);
+            },
+            Pattern::Union(inner) => {
+                for pattern in inner {
+                    pattern.collect_alternatives(alternatives, has_empty);
+                }
+            },
+            other => {
+                alternatives.insert(other);
+            }
+        }
+    }
+
+    pub(crate) fn union(first: Pattern, second: Pattern) -> Pattern {
+        let mut alternatives = BTreeSet::new();
+        let mut has_empty = false;
+
+        first.collect_alternatives(&mut alternatives, &mut has_empty);
+        second.collect_alternatives(&mut alternatives, &mut has_empty);
+
+        let mut alternatives: Vec<Pattern> = alternatives.into_iter().collect();
+
+        let res = match alternatives.len() {
+            0 => return if has_empty { Pattern::Empty } else { Pattern::Nothing },
+            1 => alternatives.remove(0),
+            _ => Pattern::Union(alternatives)
+        };
+
+        if has_empty {
+            Pattern::optional(res)
+        }
+        else {
+            res
+        }
+    }
+
+    pub(crate) fn concat(first: Pattern, second: Pattern) -> Pattern {
+        let mut parts: Vec<Pattern> = Vec::new();
+
+        for pattern in [first, second] {
+            match pattern {
+                Pattern::Nothing => return Pattern::Nothing,
+                Pattern::Empty => {},
+                Pattern::Concat(inner) => parts.extend(inner),
+                other => parts.push(other)
+            }
+        }
+
+        // Join neighbours like "aa*", "a*a" and "ab(ab)*" into "a+" and "(ab)+"
+        let mut joined: Vec<Pattern> = Vec::new();
+
+        for part in parts {
+            if let Pattern::Kleene(inner) = &part {
+                let repeated: &[Pattern] = match &**inner {
+                    Pattern::Concat(inner_parts) => inner_parts,
+                    other => std::slice::from_ref(other)
+                };
+
+                if joined.ends_with(repeated) {
+                    joined.truncate(joined.len() - repeated.len());
+                    joined.push(Pattern::Plus(inner.clone()));
+                    continue;
+                }
+            }
+
+            match joined.last() {
+                Some(Pattern::Kleene(inner)) if **inner == part => {
+                    let plus = Pattern::Plus(inner.clone());
+                    joined.pop();
+                    joined.push(plus);
+                },
+                _ => joined.push(part)
+            }
+        }
+
+        match joined.len() {
+            0 => Pattern::Empty,
+            1 => joined.remove(0),
+            _ => Pattern::Concat(joined)
+        }
+    }
+
+    pub(crate) fn optional(pattern: Pattern) -> Pattern {
+        match pattern {
+            Pattern::Nothing | Pattern::Empty => Pattern::Empty,
+            Pattern::Plus(inner) => Pattern::Kleene(inner),
+            other if other.matches_empty() => other,
+            other => Pattern::Optional(Box::new(other))
+        }
+    }
+
+    pub(crate) fn kleene(pattern: Pattern) -> Pattern {
+        match pattern {
+            Pattern::Nothing | Pattern::Empty => Pattern::Empty,
+            Pattern::Kleene(inner) | Pattern::Plus(inner) | Pattern::Optional(inner) => Pattern::kleene(*inner),
+            other => Pattern::Kleene(Box::new(other))
+        }
+    }
+
+    // Returns how tightly the pattern binds, so that we know when to put it in parentheses
+    fn precedence(&self) -> u8 {
+        match self {
+            Pattern::Union(_) => 0,
+            Pattern::Concat(_) => 1,
+            Pattern::Optional(_) | Pattern::Kleene(_) | Pattern::Plus(_) => 2,
+            Pattern::Nothing | Pattern::Empty | Pattern::Char(_) => 3
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter, min_precedence: u8) -> fmt::Result {
+        if self.precedence() < min_precedence {
+            write!(f, "({})", self)
+        }
+        else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            // The empty expression doesn't match anything, while the empty group
+            // matches only the empty string. Both only ever make up a whole pattern,
+            // because the constructors simplify them away everywhere else.
+            Pattern::Nothing => Ok(()),
+            Pattern::Empty => write!(f, "()"),
+            Pattern::Char(letter) => {
+                if SPECIAL_CHARS.contains(letter) {
+                    write!(f, "\\")?;
+                }
+
+                write!(f, "{}", letter)
+            },
+            Pattern::Concat(parts) => {
+                for part in parts {
+                    part.fmt_operand(f, 2)?;
+                }
+
+                Ok(())
+            },
+            Pattern::Union(parts) => {
+                for (i, part) in parts.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "|")?;
+                    }
+
+                    part.fmt_operand(f, 1)?;
+                }
+
+                Ok(())
+            },
+            Pattern::Optional(inner) => {
+                inner.fmt_operand(f, 3)?;
+                write!(f, "?")
+            },
+            Pattern::Kleene(inner) => {
+                inner.fmt_operand(f, 3)?;
+                write!(f, "*")
+            },
+            Pattern::Plus(inner) => {
+                inner.fmt_operand(f, 3)?;
+                write!(f, "+")
+            }
+        }
+    }
+}
+
+// Converts a DFA into a regular expression by state elimination. The DFA's transitions
+// become the edges of a generalized automaton whose edges are labeled with patterns.
+// The states are then removed one by one, replacing each path that goes through
+// the removed state with a direct edge, until only a new start and a new end state remain.
+pub(crate) struct StateEliminator {
+    edges: BTreeMap<(u32, u32), Pattern>,
+    states: BTreeSet<u32>,
+    start: u32,
+    end: u32
+}
+
+impl StateEliminator {
+    pub(crate) fn new(dfa: &DFA) -> Self {
+        let mut edges = BTreeMap::new();
+        let states = dfa.useful_states();
+
+        // The ids after the DFA's largest id are free for the new start and end states
+        let start = dfa.states.iter().next_back().map_or(0, |s| s + 1);
+        let end = start + 1;
+
+        for transition in &dfa.transitions {
+            if states.contains(&transition.from) && states.contains(&transition.to) {
+                StateEliminator::add_edge(&mut edges, transition.from, Pattern::Char(transition.label), transition.to);
+            }
+        }
+
+        if let Some(initial_state) = dfa.initial_state {
+            if states.contains(&initial_state) {
+                edges.insert((start, initial_state), Pattern::Empty);
+            }
+        }
+
+        for f in dfa.final_states.intersection(&states) {
+            edges.insert((*f, end), Pattern::Empty);
+        }
+
+        StateEliminator {
+            edges,
+            states,
+            start,
+            end
+        }
+    }
+
+    fn add_edge(edges: &mut BTreeMap<(u32, u32), Pattern>, from: u32, pattern: Pattern, to: u32) {
+        let res = match edges.remove(&(from, to)) {
+            Some(existing) => Pattern::union(existing, pattern),
+            None           => pattern
+        };
+
+        edges.insert((from, to), res);
+    }
+
+    // Removing states with few incoming and outgoing edges first keeps the
+    // intermediate patterns small
+    fn next_state(&self) -> Option<u32> {
+        self.states.iter().cloned().min_by_key(|state| {
+            let incoming = self.edges.keys().filter(|(from, to)| to == state && from != state).count();
+            let outgoing = self.edges.keys().filter(|(from, to)| from == state && to != state).count();
+
+            incoming * outgoing
+        })
+    }
+
+    fn eliminate_state(&mut self, state: u32) {
+        let loop_pattern = match self.edges.remove(&(state, state)) {
+            Some(pattern) => Pattern::kleene(pattern),
+            None          => Pattern::Empty
+        };
+
+        let incoming: Vec<(u32, Pattern)> = self.edges.iter()
+                                                      .filter(|((_, to), _)| *to == state)
+                                                      .map(|((from, _), pattern)| (*from, pattern.clone()))
+                                                      .collect();
+        let outgoing: Vec<(u32, Pattern)> = self.edges.iter()
+                                                      .filter(|((from, _), _)| *from == state)
+                                                      .map(|((_, to), pattern)| (*to, pattern.clone()))
+                                                      .collect();
+
+        for (from, _) in &incoming {
+            self.edges.remove(&(*from, state));
+        }
+
+        for (to, _) in &outgoing {
+            self.edges.remove(&(state, *to));
+        }
+
+        for (from, in_pattern) in &incoming {
+            for (to, out_pattern) in &outgoing {
+                let through = Pattern::concat(in_pattern.clone(), loop_pattern.clone());
+                let through = Pattern::concat(through, out_pattern.clone());
+
+                StateEliminator::add_edge(&mut self.edges, *from, through, *to);
+            }
+        }
+
+        self.states.remove(&state);
+    }
+
+    pub(crate) fn eliminate(mut self) -> Pattern {
+        while let Some(state) = self.next_state() {
+            self.eliminate_state(state);
+        }
+
+        self.edges.remove(&(self.start, self.end)).unwrap_or(Pattern::Nothing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transition::Transition;
+
+    #[test]
+    fn simplify_union() {
+        let pattern = Pattern::union(Pattern::Char('b'), Pattern::union(Pattern::Char('a'), Pattern::Empty));
+        assert_eq!(pattern.to_string(), "(a|b)?");
+
+        let pattern = Pattern::union(Pattern::Char('a'), Pattern::Nothing);
+        assert_eq!(pattern.to_string(), "a");
+    }
+
+    #[test]
+    fn simplify_concat() {
+        let a = Pattern::Char('a');
+
+        let pattern = Pattern::concat(a.clone(), Pattern::kleene(a.clone()));
+        assert_eq!(pattern.to_string(), "a+");
+
+        let ab = Pattern::concat(a.clone(), Pattern::Char('b'));
+        let pattern = Pattern::concat(ab.clone(), Pattern::kleene(ab));
+        assert_eq!(pattern.to_string(), "(ab)+");
+
+        let pattern = Pattern::concat(Pattern::Empty, a.clone());
+        assert_eq!(pattern.to_string(), "a");
+
+        let pattern = Pattern::concat(Pattern::Nothing, a);
+        assert_eq!(pattern, Pattern::Nothing);
+    }
+
+    #[test]
+    fn display_escapes_special_chars() {
+        let pattern = Pattern::concat(Pattern::Char('*'), Pattern::Char('('));
+        assert_eq!(pattern.to_string(), "\\*\\(");
+    }
+
+    #[test]
+    fn display_special_languages() {
+        assert_eq!(Pattern::Nothing.to_string(), "");
+        assert_eq!(Pattern::Empty.to_string(), "()");
+        assert_eq!(Pattern::kleene(Pattern::Nothing).to_string(), "()");
+        assert_eq!(Pattern::optional(Pattern::Char('a')).to_string(), "a?");
+    }
+
+    #[test]
+    fn eliminate() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0, 1, 2];
+        dfa.counter.value = 3;
+
+        dfa.initial_state = Some(1);
+        dfa.final_states = set![2];
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 0),
+            Transition::new(0, 'b', 0),
+            Transition::new(1, 'a', 2),
+            Transition::new(1, 'b', 0),
+            Transition::new(2, 'a', 0),
+            Transition::new(2, 'b', 1)
+        ];
+
+        assert_eq!(StateEliminator::new(&dfa).eliminate().to_string(), "a(ba)*");
+    }
+}
@@ -1,26 +1,229 @@
-use crate::nfa::NFA;
+use crate::parser::Parser;
 use crate::dfa::DFA;
-use crate::minimizer::Minimizer;
-use crate::determinizer::Determinizer;
+use crate::captures::CaptureGroups;
+use crate::strings::Strings;
+use crate::reader;
+#[cfg(feature = "rand")]
+use crate::sampling::Sampler;
 
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use std::collections::BTreeSet;
+use std::io::{self, Read};
+use std::ops::Range;
+use std::sync::OnceLock;
+
+pub use crate::parser::{ParseError, ParseErrorKind, MAX_NESTING};
+pub use crate::trace::{Trace, Step, TraceFailure, FailureKind};
+pub use crate::matcher::{Matcher, Snapshot};
+pub use crate::expression_set::{ExpressionSet, PatternError};
+pub use crate::search::{Matches, Split};
+pub use crate::replace::Replacer;
+pub use crate::captures::Captures;
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Expression {
-    dfa: DFA
+    dfa: DFA,
+    // None if the expression has no groups. Expressions that were serialized before
+    // they had capture groups have none either.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    groups: Option<CaptureGroups>,
+    // The live states of the automaton, computed on the first search for a match
+    // in a part of a text and reused by the later ones
+    #[cfg_attr(feature = "serde", serde(skip))]
+    live_states: OnceLock<BTreeSet<u32>>
 }
 
 impl Expression {
+    // Builds an expression in the basic syntax, where every string is valid and only
+    // '|', '?', '*' and '+' are operators. Parentheses and '\\' are matched literally,
+    // so "(a|b)" matches "(a" and "b)". Use parse for groups and escaping.
     pub fn new(string: &str) -> Self {
-        let nfa = NFA::from_string(string);
-        let dfa = Determinizer::new(nfa).determinize().take();
-        let dfa = Minimizer::new(dfa).minimize().take();
+        Expression::from_dfa(DFA::from_nfa(Parser::new(string).parse_basic()).minimize())
+    }
+
+    // Builds an expression in the full syntax with groups and escaping, which is
+    // described in the README. Returns an error if the expression is invalid.
+    pub fn parse(string: &str) -> Result<Self, ParseError> {
+        let (nfa, names) = Parser::new(string).parse_captures()?;
+
+        // The automaton with the groups accepts the same strings, so the DFA can be
+        // built from it. It's only kept if there are groups to find.
+        let (dfa, groups) = if names.is_empty() {
+            (DFA::from_nfa(nfa), None)
+        }
+        else {
+            (DFA::from_nfa(nfa.clone()), Some(CaptureGroups::new(nfa, names)))
+        };
 
+        Ok(Expression {
+            groups,
+            ..Expression::from_dfa(dfa.minimize())
+        })
+    }
+
+    pub(crate) fn from_dfa(dfa: DFA) -> Self {
         Expression {
-            dfa
+            dfa,
+            groups: None,
+            live_states: OnceLock::new()
         }
     }
 
     pub fn matches(&self, text: &str) -> bool {
         self.dfa.matches(text)
     }
+
+    // Returns whether the whole UTF-8 text of the reader is matched. The text is read in
+    // fixed size pieces and reading stops as soon as no match is possible anymore.
+    // Invalid UTF-8 is reported as an error of kind InvalidData.
+    pub fn matches_reader<R: Read>(&self, reader: R) -> io::Result<bool> {
+        reader::matches_reader(&self.dfa, reader)
+    }
+
+    // Returns the byte range of the first match in the UTF-8 text of the reader, the same
+    // one that find returns for the text. Reading stops once no more text can change the
+    // match and the memory used doesn't depend on the text.
+    pub fn find_in_reader<R: Read>(&self, reader: R) -> io::Result<Option<Range<u64>>> {
+        reader::find_in_reader(&self.dfa, reader)
+    }
+
+    // Returns a matcher that is fed the text piece by piece instead of all at once
+    pub fn matcher(&self) -> Matcher<'_> {
+        Matcher::new(&self.dfa)
+    }
+
+    // Returns the states that the minimized automaton goes through on the text and,
+    // if the text is not matched, why it was rejected
+    pub fn trace(&self, text: &str) -> Trace {
+        Trace::new(&self.dfa, text)
+    }
+
+    // Returns whether any part of the text is matched by the expression
+    pub fn contains_match(&self, text: &str) -> bool {
+        self.dfa.matches_substring(text, self.live_states.get_or_init(|| self.dfa.live_states()))
+    }
+
+    // Returns the byte range of the first match in the text. It's the match that starts
+    // first and of those starting there, the longest one. See find_in_reader for a reader.
+    pub fn find(&self, text: &str) -> Option<Range<usize>> {
+        self.find_iter(text).next()
+    }
+
+    // Returns the byte ranges of the non-overlapping matches in the text. See find.
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> Matches<'a> {
+        Matches::new(&self.dfa, text)
+    }
+
+    // Returns the first match in the text like find, together with the capture groups in it
+    pub fn captures<'a>(&'a self, text: &'a str) -> Option<Captures<'a>> {
+        self.find(text).map(|range| Captures::new(self.groups.as_ref(), text, range))
+    }
+
+    // Returns the text with every match replaced. See Replacer for what the
+    // replacement can be.
+    pub fn replace_all<R: Replacer>(&self, text: &str, replacement: R) -> String {
+        self.replace_n(text, usize::MAX, replacement)
+    }
+
+    // Returns the text with the first count matches replaced
+    pub fn replace_n<R: Replacer>(&self, text: &str, count: usize, mut replacement: R) -> String {
+        let mut res = String::with_capacity(text.len());
+        let mut last_end = 0;
+
+        for range in self.find_iter(text).take(count) {
+            res.push_str(&text[last_end..range.start]);
+            last_end = range.end;
+            replacement.append_replacement(&Captures::new(self.groups.as_ref(), text, range), &mut res);
+        }
+
+        res.push_str(&text[last_end..]);
+        res
+    }
+
+    // Returns the parts of the text between the non-overlapping matches, like str::split.
+    // See find_iter for how the matches are found.
+    pub fn split<'a>(&'a self, text: &'a str) -> Split<'a> {
+        Split::new(&self.dfa, text, usize::MAX)
+    }
+
+    // Same as split, but returns at most count parts. The last one is the rest of the text.
+    pub fn splitn<'a>(&'a self, text: &'a str, count: usize) -> Split<'a> {
+        Split::new(&self.dfa, text, count)
+    }
+
+    // Returns the minimized automaton that the expression uses for matching
+    pub fn dfa(&self) -> &DFA {
+        &self.dfa
+    }
+
+    // Returns whether both expressions match exactly the same strings
+    pub fn is_equivalent(&self, other: &Expression) -> bool {
+        self.distinguishing_string(other).is_none()
+    }
+
+    // Returns the shortest string that is matched by exactly one of the expressions
+    // or None if they are equivalent
+    pub fn distinguishing_string(&self, other: &Expression) -> Option<String> {
+        self.dfa.distinguishing_string(&other.dfa)
+    }
+
+    // Returns a Graphviz DOT description of the minimized automaton. See DFA::to_dot.
+    pub fn to_dot(&self, hide_dead_states: bool) -> String {
+        self.dfa.to_dot(hide_dead_states)
+    }
+
+    // Returns all strings that the expression matches in shortlex order.
+    // Shorter strings come first and strings of the same length are ordered
+    // lexicographically. The iterator is infinite if the expression matches
+    // infinitely many strings.
+    pub fn strings(&self) -> impl Iterator<Item = String> + '_ {
+        Strings::new(&self.dfa, None)
+    }
+
+    // Same as strings, but stops after the strings that are max_length chars long
+    pub fn strings_up_to(&self, max_length: usize) -> impl Iterator<Item = String> + '_ {
+        Strings::new(&self.dfa, Some(max_length))
+    }
+
+    // Returns how many strings of exactly the given length the expression matches
+    // or None if the count doesn't fit into an u128
+    pub fn count_of_length(&self, length: usize) -> Option<u128> {
+        self.dfa.count_of_length(length)
+    }
+
+    // Returns how many strings of length up to max_length (inclusive) the expression
+    // matches or None if the count doesn't fit into an u128
+    pub fn count_up_to(&self, max_length: usize) -> Option<u128> {
+        self.dfa.count_up_to(max_length)
+    }
+
+    // Builds an expression that matches the same strings as this one out of
+    // the minimized automaton. The result can be parsed back with Expression::parse.
+    pub fn to_expression_string(&self) -> String {
+        self.dfa.to_expression_string()
+    }
+
+    // Returns an uniformly random string of the given length that the expression matches.
+    // Returns None if there are no such strings or if their count doesn't fit into an u128.
+    #[cfg(feature = "rand")]
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R, length: usize) -> Option<String> {
+        Sampler::new(&self.dfa).sample(rng, length)
+    }
+
+    // Returns a random string that the expression matches by taking random transitions
+    // and stopping in final states with the given probability. Shorter strings are more
+    // likely than longer ones, so the result is not uniformly random.
+    // Returns None if the expression doesn't match any strings or if the probability
+    // is not in (0, 1]. With a probability of 0 the walk might never stop.
+    #[cfg(feature = "rand")]
+    pub fn random_walk<R: Rng + ?Sized>(&self, rng: &mut R, stop_probability: f64) -> Option<String> {
+        Sampler::new(&self.dfa).random_walk(rng, stop_probability)
+    }
 }
 
 #[cfg(test)]
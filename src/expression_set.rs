@@ -0,0 +1,172 @@
+use crate::nfa::NFA;
+use crate::dfa::DFA;
+use crate::determinizer::Determinizer;
+use crate::parser::ParseError;
+
+use std::collections::{BTreeSet, BTreeMap};
+use std::error::Error;
+use std::fmt;
+
+// A pattern of the set that couldn't be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatternError {
+    pub index: usize,
+    pub error: ParseError
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "pattern {}: {}", self.index, self.error)
+    }
+}
+
+impl Error for PatternError {}
+
+// Matches a text against many patterns at once. The NFAs of all patterns are joined
+// and determinized together, so a text is read only once no matter how many patterns
+// there are. Each state of the DFA knows which patterns are matched when it is reached.
+//
+// The DFA is not minimized, because the minimizer would merge final states that
+// match different patterns.
+#[derive(Debug)]
+pub struct ExpressionSet {
+    pub(crate) dfa: DFA,
+    pub(crate) live_states: BTreeSet<u32>,
+    pub(crate) matched_patterns: BTreeMap<u32, BTreeSet<usize>>,
+    pattern_count: usize
+}
+
+impl ExpressionSet {
+    // Panics if any of the patterns is invalid. Use parse to handle the error instead.
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        match ExpressionSet::parse(patterns) {
+            Ok(set)    => set,
+            Err(error) => panic!("Invalid expression set: {}", error)
+        }
+    }
+
+    pub fn parse<S: AsRef<str>>(patterns: &[S]) -> Result<Self, PatternError> {
+        let nfas = patterns.iter()
+                           .enumerate()
+                           .map(|(index, pattern)| NFA::from_string(pattern.as_ref()).map_err(|error| PatternError { index, error }))
+                           .collect::<Result<Vec<NFA>, PatternError>>()?;
+
+        Ok(ExpressionSet::from_nfas(nfas))
+    }
+
+    pub(crate) fn from_nfas(nfas: Vec<NFA>) -> Self {
+        let pattern_count = nfas.len();
+        let mut combined = NFA::new();
+
+        // The final states of the combined NFA and the patterns they belong to
+        let mut final_state_patterns = BTreeMap::new();
+
+        for (index, mut nfa) in nfas.into_iter().enumerate() {
+            // The union shifts the states of nfa by the state count of combined
+            let shift = combined.counter.value;
+
+            for state in &nfa.final_states {
+                final_state_patterns.insert(state + shift, index);
+            }
+
+            nfa.union(&combined);
+            combined = nfa;
+        }
+
+        let (dfa, subsets) = Determinizer::new(combined).determinize().take_with_subsets();
+        let matched_patterns: BTreeMap<u32, BTreeSet<usize>> = subsets.into_iter().filter_map(|(state, subset)| {
+            let patterns: BTreeSet<usize> = subset.iter().filter_map(|nfa_state| final_state_patterns.get(nfa_state)).cloned().collect();
+
+            if patterns.is_empty() { None } else { Some((state, patterns)) }
+        }).collect();
+
+        ExpressionSet {
+            live_states: dfa.live_states(),
+            dfa,
+            matched_patterns,
+            pattern_count
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pattern_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pattern_count == 0
+    }
+
+    // Returns the indices of the patterns that match the text
+    pub fn matches(&self, text: &str) -> BTreeSet<usize> {
+        self.patterns_matched_in(self.final_state(text.chars()))
+    }
+
+    // Returns whether any of the patterns matches the text
+    pub fn is_match(&self, text: &str) -> bool {
+        self.final_state(text.chars()).is_some_and(|state| self.matched_patterns.contains_key(&state))
+    }
+
+    pub(crate) fn patterns_matched_in(&self, state: Option<u32>) -> BTreeSet<usize> {
+        state.and_then(|state| self.matched_patterns.get(&state))
+             .cloned()
+             .unwrap_or_default()
+    }
+
+    // Returns the state the automaton ends in or None if it gets stuck or
+    // enters a state from which no pattern can be matched
+    pub(crate) fn final_state<I: Iterator<Item = char>>(&self, chars: I) -> Option<u32> {
+        let mut state = self.dfa.initial_state?;
+
+        for ch in chars {
+            state = self.dfa.reachable(state, ch).filter(|state| self.live_states.contains(state))?;
+        }
+
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tagged_final_states() {
+        let set = ExpressionSet::new(&["ab", "a*"]);
+
+        // Pattern 0 is shifted by the two states of pattern 1 in the combined NFA
+        assert_eq!(set.matched_patterns.values().cloned().collect::<BTreeSet<_>>(), set![set![0], set![1]]);
+        assert_eq!(set.matches("ab"), set![0]);
+        assert_eq!(set.matches("aa"), set![1]);
+        assert_eq!(set.matches("b"), BTreeSet::new());
+    }
+
+    #[test]
+    fn overlapping_patterns() {
+        let set = ExpressionSet::new(&["a+", "ab?", "b"]);
+
+        assert_eq!(set.matches("a"), set![0, 1]);
+        assert_eq!(set.matches("aa"), set![0]);
+        assert_eq!(set.matches("ab"), set![1]);
+        assert_eq!(set.matches("b"), set![2]);
+        assert!(!set.is_match("ba"));
+        assert!(!set.is_match("c"));
+    }
+
+    #[test]
+    fn empty_set() {
+        let patterns: [&str; 0] = [];
+        let set = ExpressionSet::new(&patterns);
+
+        assert!(set.is_empty());
+        assert!(!set.is_match(""));
+        assert_eq!(set.matches("a"), BTreeSet::new());
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        let error = ExpressionSet::parse(&["a", "b|", "(c"]).unwrap_err();
+
+        assert_eq!(error.index, 2);
+        assert_eq!(error.to_string(), "pattern 2: group opened at position 0 is never closed");
+    }
+}
@@ -0,0 +1,225 @@
+// C interface for building expressions and matching text against them.
+// The declarations for C are in include/automata.h, which the build script of the
+// automata-ffi crate generates from this file with cbindgen.
+
+use crate::expression::{Expression, ParseErrorKind};
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+pub const AUTOMATA_OK: c_int = 0;
+pub const AUTOMATA_ERROR_NULL_POINTER: c_int = 1;
+pub const AUTOMATA_ERROR_INVALID_UTF8: c_int = 2;
+pub const AUTOMATA_ERROR_MISSING_OPERAND: c_int = 3;
+pub const AUTOMATA_ERROR_UNCLOSED_GROUP: c_int = 4;
+pub const AUTOMATA_ERROR_UNMATCHED_PARENTHESIS: c_int = 5;
+pub const AUTOMATA_ERROR_DANGLING_ESCAPE: c_int = 6;
+pub const AUTOMATA_ERROR_INVALID_GROUP_NAME: c_int = 7;
+pub const AUTOMATA_ERROR_DUPLICATE_GROUP_NAME: c_int = 8;
+pub const AUTOMATA_ERROR_NESTING_TOO_DEEP: c_int = 9;
+pub const AUTOMATA_ERROR_PANIC: c_int = 10;
+
+thread_local! {
+    // The message of the last error that happened in the current thread
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(code: c_int, message: String) -> c_int {
+    // The messages never contain nul bytes, since they are built by this crate
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(message));
+    code
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = None);
+}
+
+// Runs f and turns a panic into AUTOMATA_ERROR_PANIC, because unwinding into the C
+// caller is undefined behavior
+fn catch_panic<T, F: FnOnce() -> T>(f: F) -> Result<T, c_int> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let message = match payload.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None          => payload.downcast_ref::<String>().cloned().unwrap_or_default()
+        };
+
+        set_last_error(AUTOMATA_ERROR_PANIC, format!("internal error: {}", message))
+    })
+}
+
+unsafe fn read_str<'a>(string: *const c_char, name: &str) -> Result<&'a str, c_int> {
+    if string.is_null() {
+        return Err(set_last_error(AUTOMATA_ERROR_NULL_POINTER, format!("{} is null", name)));
+    }
+
+    CStr::from_ptr(string).to_str().map_err(|error| {
+        set_last_error(AUTOMATA_ERROR_INVALID_UTF8, format!("{} is not valid UTF-8: {}", name, error))
+    })
+}
+
+/// Builds an expression from a nul-terminated UTF-8 pattern and stores it in `*expression`.
+/// Returns `AUTOMATA_OK` on success and an error code otherwise, in which case
+/// `automata_last_error_message` describes the error.
+///
+/// # Safety
+///
+/// `pattern` must be null or a valid nul-terminated string and `expression` must be
+/// null or valid for writes. The stored expression has to be freed with
+/// `automata_expression_free`.
+#[no_mangle]
+pub unsafe extern "C" fn automata_expression_new(pattern: *const c_char, expression: *mut *mut Expression) -> c_int {
+    clear_last_error();
+
+    if expression.is_null() {
+        return set_last_error(AUTOMATA_ERROR_NULL_POINTER, "expression is null".to_string());
+    }
+
+    let pattern = match read_str(pattern, "pattern") {
+        Ok(pattern) => pattern,
+        Err(code)   => return code
+    };
+
+    match catch_panic(|| Expression::parse(pattern)) {
+        Ok(Ok(parsed)) => {
+            *expression = Box::into_raw(Box::new(parsed));
+            AUTOMATA_OK
+        },
+        Ok(Err(error)) => {
+            let code = match error.kind {
+                ParseErrorKind::MissingOperand(_)    => AUTOMATA_ERROR_MISSING_OPERAND,
+                ParseErrorKind::UnclosedGroup        => AUTOMATA_ERROR_UNCLOSED_GROUP,
+                ParseErrorKind::UnmatchedParenthesis => AUTOMATA_ERROR_UNMATCHED_PARENTHESIS,
+                ParseErrorKind::DanglingEscape       => AUTOMATA_ERROR_DANGLING_ESCAPE,
+                ParseErrorKind::InvalidGroupName     => AUTOMATA_ERROR_INVALID_GROUP_NAME,
+                ParseErrorKind::DuplicateGroupName   => AUTOMATA_ERROR_DUPLICATE_GROUP_NAME,
+                ParseErrorKind::NestingTooDeep       => AUTOMATA_ERROR_NESTING_TOO_DEEP
+            };
+
+            set_last_error(code, error.to_string())
+        },
+        Err(code) => code
+    }
+}
+
+/// Returns 1 if the expression matches the nul-terminated UTF-8 text and 0 if it doesn't,
+/// if the arguments are invalid or if the library panicked. In the last two cases
+/// `automata_last_error_message` describes the error.
+///
+/// # Safety
+///
+/// `expression` must be null or an expression returned by `automata_expression_new` that
+/// hasn't been freed. `text` must be null or a valid nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn automata_expression_matches(expression: *const Expression, text: *const c_char) -> c_int {
+    clear_last_error();
+
+    if expression.is_null() {
+        set_last_error(AUTOMATA_ERROR_NULL_POINTER, "expression is null".to_string());
+        return 0;
+    }
+
+    match read_str(text, "text") {
+        Ok(text) => catch_panic(|| (*expression).matches(text) as c_int).unwrap_or(0),
+        Err(_)   => 0
+    }
+}
+
+/// Frees an expression. Does nothing if `expression` is null.
+///
+/// # Safety
+///
+/// `expression` must be null or an expression returned by `automata_expression_new`
+/// that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn automata_expression_free(expression: *mut Expression) {
+    if !expression.is_null() {
+        drop(Box::from_raw(expression));
+    }
+}
+
+/// Returns the message of the last error in the current thread or null if the last call
+/// succeeded. The message is valid until the next call into the library from the same thread.
+#[no_mangle]
+pub extern "C" fn automata_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last_error| match &*last_error.borrow() {
+        Some(message) => message.as_ptr(),
+        None          => ptr::null()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error_message() -> Option<String> {
+        let message = automata_last_error_message();
+
+        if message.is_null() {
+            None
+        }
+        else {
+            Some(unsafe { CStr::from_ptr(message) }.to_str().unwrap().to_string())
+        }
+    }
+
+    #[test]
+    fn new_matches_and_free() {
+        let pattern = CString::new("a+bc*|ca*").unwrap();
+        let mut expression = ptr::null_mut();
+
+        unsafe {
+            assert_eq!(automata_expression_new(pattern.as_ptr(), &mut expression), AUTOMATA_OK);
+            assert_eq!(last_error_message(), None);
+
+            let matching = CString::new("aabcc").unwrap();
+            let not_matching = CString::new("bc").unwrap();
+
+            assert_eq!(automata_expression_matches(expression, matching.as_ptr()), 1);
+            assert_eq!(automata_expression_matches(expression, not_matching.as_ptr()), 0);
+
+            automata_expression_free(expression);
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let pattern = CString::new("a(b").unwrap();
+        let mut expression = ptr::null_mut();
+
+        unsafe {
+            assert_eq!(automata_expression_new(pattern.as_ptr(), &mut expression), AUTOMATA_ERROR_UNCLOSED_GROUP);
+        }
+
+        assert!(expression.is_null());
+        assert_eq!(last_error_message().unwrap(), "group opened at position 1 is never closed");
+    }
+
+    #[test]
+    fn panics_become_errors() {
+        assert_eq!(catch_panic(|| 1), Ok(1));
+        assert_eq!(catch_panic(|| -> i32 { panic!("at the disco") }), Err(AUTOMATA_ERROR_PANIC));
+        assert_eq!(last_error_message().unwrap(), "internal error: at the disco");
+
+        assert_eq!(catch_panic(|| -> i32 { panic!("{} disco", "at the") }), Err(AUTOMATA_ERROR_PANIC));
+        assert_eq!(last_error_message().unwrap(), "internal error: at the disco");
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let invalid_utf8 = [0xffu8, 0];
+        let mut expression = ptr::null_mut();
+
+        unsafe {
+            assert_eq!(automata_expression_new(ptr::null(), &mut expression), AUTOMATA_ERROR_NULL_POINTER);
+            assert_eq!(automata_expression_new(invalid_utf8.as_ptr() as *const c_char, &mut expression), AUTOMATA_ERROR_INVALID_UTF8);
+            assert_eq!(automata_expression_matches(ptr::null(), invalid_utf8.as_ptr() as *const c_char), 0);
+            assert_eq!(last_error_message().unwrap(), "expression is null");
+
+            automata_expression_free(ptr::null_mut());
+        }
+    }
+}
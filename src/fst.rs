@@ -0,0 +1,481 @@
+use crate::dfa::DFA;
+use crate::expression::Expression;
+use crate::transition::Transition;
+
+use std::collections::{BTreeSet, BTreeMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+
+// A key was inserted after a key that is not smaller than it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyOrderError {
+    pub previous: String,
+    pub key: String
+}
+
+impl fmt::Display for KeyOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "keys must be sorted and unique, but {:?} comes after {:?}", self.key, self.previous)
+    }
+}
+
+impl Error for KeyOrderError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Node {
+    is_final: bool,
+    final_output: u64,
+    // The char, the output and the target of each edge, sorted by the char
+    edges: Vec<(char, u64, usize)>
+}
+
+impl Node {
+    fn new() -> Self {
+        Node {
+            is_final: false,
+            final_output: 0,
+            edges: vec![]
+        }
+    }
+
+    // Adds the output to every way out of the node
+    fn prepend_output(&mut self, output: u64) {
+        if self.is_final {
+            self.final_output += output;
+        }
+
+        for edge in &mut self.edges {
+            edge.1 += output;
+        }
+    }
+}
+
+// Builds a minimal acyclic transducer from keys in sorted order. Like DawgBuilder, it
+// keeps the path of the last key unfinished and replaces its nodes with equivalent ones
+// once no more keys can pass through them. The value of a key is the sum of the outputs
+// on its path and of the final output of its last state. The outputs are pushed as close
+// to the initial state as possible, so that keys with common prefixes share their outputs.
+#[derive(Debug)]
+pub struct FstMapBuilder {
+    nodes: Vec<Node>,
+    register: BTreeMap<Node, usize>,
+    // The nodes of the last key's path. The last edge of each node leads to the next one.
+    unfinished: Vec<Node>,
+    previous: Option<String>,
+    len: usize
+}
+
+impl FstMapBuilder {
+    pub fn new() -> Self {
+        FstMapBuilder {
+            nodes: vec![],
+            register: BTreeMap::new(),
+            unfinished: vec![Node::new()],
+            previous: None,
+            len: 0
+        }
+    }
+
+    pub fn insert(&mut self, key: &str, value: u64) -> Result<(), KeyOrderError> {
+        let common_prefix = match &self.previous {
+            Some(previous) if key <= previous.as_str() => {
+                return Err(KeyOrderError { previous: previous.clone(), key: key.to_string() });
+            },
+            Some(previous) => previous.chars().zip(key.chars()).take_while(|(a, b)| a == b).count(),
+            None => 0
+        };
+
+        self.finish_nodes(common_prefix);
+
+        // Keep only the part of the outputs on the common prefix that both keys share
+        // and push the rest further down the previous key's path
+        let mut value = value;
+
+        for depth in 0..common_prefix {
+            let edge = self.unfinished[depth].edges.last_mut().expect("Nodes on the path have an edge");
+            let common = edge.1.min(value);
+            let rest = edge.1 - common;
+
+            edge.1 = common;
+            value -= common;
+            self.unfinished[depth + 1].prepend_output(rest);
+        }
+
+        let suffix: Vec<char> = key.chars().skip(common_prefix).collect();
+
+        match suffix.split_first() {
+            Some((first, rest)) => {
+                self.unfinished[common_prefix].edges.push((*first, value, usize::MAX));
+
+                for letter in rest {
+                    let mut node = Node::new();
+                    node.edges.push((*letter, 0, usize::MAX));
+                    self.unfinished.push(node);
+                }
+
+                let mut last = Node::new();
+                last.is_final = true;
+                self.unfinished.push(last);
+            },
+            // Only the first key can be empty, because the keys are sorted
+            None => {
+                let node = &mut self.unfinished[common_prefix];
+                node.is_final = true;
+                node.final_output = value;
+            }
+        }
+
+        self.previous = Some(key.to_string());
+        self.len += 1;
+
+        Ok(())
+    }
+
+    // Replaces or registers the unfinished nodes that are deeper than the depth
+    fn finish_nodes(&mut self, depth: usize) {
+        while self.unfinished.len() > depth + 1 {
+            let node = self.unfinished.pop().expect("Checked by the loop condition");
+            let id = self.finish_node(node);
+
+            let edge = self.unfinished.last_mut().and_then(|parent| parent.edges.last_mut()).expect("Nodes on the path have an edge");
+            edge.2 = id;
+        }
+    }
+
+    fn finish_node(&mut self, node: Node) -> usize {
+        if let Some(id) = self.register.get(&node) {
+            return *id;
+        }
+
+        let id = self.nodes.len();
+        self.nodes.push(node.clone());
+        self.register.insert(node, id);
+        id
+    }
+
+    pub fn finish(mut self) -> FstMap {
+        self.finish_nodes(0);
+
+        let root = self.unfinished.pop().expect("The root is always unfinished");
+        let root = self.finish_node(root);
+
+        let mut map = FstMap {
+            dfa: DFA::new(),
+            outputs: BTreeMap::new(),
+            final_outputs: BTreeMap::new(),
+            len: self.len
+        };
+
+        let mut state_ids = BTreeMap::new();
+        let mut queue = VecDeque::new();
+
+        state_ids.insert(root, map.dfa.counter.tick());
+        queue.push_back(root);
+
+        while let Some(node) = queue.pop_front() {
+            let state = state_ids[&node];
+            let node = &self.nodes[node];
+            map.dfa.states.insert(state);
+
+            if node.is_final {
+                map.dfa.final_states.insert(state);
+                map.final_outputs.insert(state, node.final_output);
+            }
+
+            for (letter, output, target) in &node.edges {
+                let target_state = match state_ids.get(target) {
+                    Some(target_state) => *target_state,
+                    None => {
+                        let target_state = map.dfa.counter.tick();
+                        state_ids.insert(*target, target_state);
+                        queue.push_back(*target);
+                        target_state
+                    }
+                };
+
+                map.dfa.alphabet.insert(*letter);
+                map.dfa.transitions.insert(Transition::new(state, *letter, target_state));
+                map.outputs.insert((state, *letter), *output);
+            }
+        }
+
+        map.dfa.initial_state = Some(0);
+        map
+    }
+}
+
+impl Default for FstMapBuilder {
+    fn default() -> Self {
+        FstMapBuilder::new()
+    }
+}
+
+// Maps strings to u64 values with a minimal acyclic finite state transducer. The keys
+// are the strings accepted by the DFA and each transition and final state adds its
+// output to the value.
+#[derive(Debug)]
+pub struct FstMap {
+    dfa: DFA,
+    outputs: BTreeMap<(u32, char), u64>,
+    final_outputs: BTreeMap<u32, u64>,
+    len: usize
+}
+
+impl FstMap {
+    // Builds the map out of keys in sorted order without duplicates
+    pub fn from_sorted<I: IntoIterator<Item = (S, u64)>, S: AsRef<str>>(entries: I) -> Result<Self, KeyOrderError> {
+        let mut builder = FstMapBuilder::new();
+
+        for (key, value) in entries {
+            builder.insert(key.as_ref(), value)?;
+        }
+
+        Ok(builder.finish())
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, key: &str) -> Option<u64> {
+        let (state, value) = self.walk(key)?;
+        self.final_outputs.get(&state).map(|output| value + output)
+    }
+
+    // Returns the state after the prefix and the sum of the outputs on the way
+    fn walk(&self, prefix: &str) -> Option<(u32, u64)> {
+        prefix.chars().try_fold((self.dfa.initial_state?, 0), |(state, value), ch| {
+            Some((self.dfa.reachable(state, ch)?, value + self.outputs[&(state, ch)]))
+        })
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    // Returns all keys with their values in sorted order
+    pub fn iter(&self) -> Stream<'_> {
+        self.prefix("")
+    }
+
+    // Returns the keys that start with the prefix with their values in sorted order
+    pub fn prefix(&self, prefix: &str) -> Stream<'_> {
+        let mut stream = Stream::new(self, None);
+        stream.stack = self.walk(prefix).map(|(state, value)| (prefix.to_string(), state, value, None)).into_iter().collect();
+        stream
+    }
+
+    // Returns the keys in the range with their values in sorted order
+    pub fn range<'r, R: RangeBounds<&'r str>>(&self, range: R) -> Stream<'_> {
+        let mut stream = Stream::new(self, None);
+
+        stream.start = match range.start_bound() {
+            Bound::Included(start) => Bound::Included(start.to_string()),
+            Bound::Excluded(start) => Bound::Excluded(start.to_string()),
+            Bound::Unbounded       => Bound::Unbounded
+        };
+
+        stream.end = match range.end_bound() {
+            Bound::Included(end) => Bound::Included(end.to_string()),
+            Bound::Excluded(end) => Bound::Excluded(end.to_string()),
+            Bound::Unbounded     => Bound::Unbounded
+        };
+
+        stream
+    }
+
+    // Returns the keys that the expression matches with their values in sorted order.
+    // The map and the expression are walked together, so the parts of the map that
+    // the expression can't match are never visited.
+    pub fn search<'a>(&'a self, expression: &'a Expression) -> Stream<'a> {
+        Stream::new(self, Some(expression.dfa()))
+    }
+
+    // Returns the automaton that accepts the keys
+    pub fn dfa(&self) -> &DFA {
+        &self.dfa
+    }
+}
+
+// Walks the transducer depth first and returns the keys in sorted order. Each entry
+// on the stack is a prefix with its state, the sum of the outputs on its path and
+// the state of the expression that the keys are filtered with.
+pub struct Stream<'a> {
+    map: &'a FstMap,
+    stack: Vec<(String, u32, u64, Option<u32>)>,
+    filter: Option<(&'a DFA, BTreeSet<u32>)>,
+    start: Bound<String>,
+    end: Bound<String>
+}
+
+impl<'a> Stream<'a> {
+    fn new(map: &'a FstMap, filter: Option<&'a DFA>) -> Self {
+        let filter = filter.map(|dfa| (dfa, dfa.live_states()));
+        let filter_state = filter.as_ref().and_then(|(dfa, _)| dfa.initial_state);
+        let mut stack = vec![];
+
+        if let Some(state) = map.dfa.initial_state {
+            if filter.is_none() || filter_state.is_some() {
+                stack.push((String::new(), state, 0, filter_state));
+            }
+        }
+
+        Stream {
+            map,
+            stack,
+            filter,
+            start: Bound::Unbounded,
+            end: Bound::Unbounded
+        }
+    }
+
+    // Returns whether a key with the prefix or any of its continuations can be
+    // after the start of the range
+    fn can_reach_start(&self, prefix: &str) -> bool {
+        match &self.start {
+            Bound::Included(start) | Bound::Excluded(start) => prefix >= start.as_str() || start.starts_with(prefix),
+            Bound::Unbounded => true
+        }
+    }
+
+    fn after_start(&self, key: &str) -> bool {
+        match &self.start {
+            Bound::Included(start) => key >= start.as_str(),
+            Bound::Excluded(start) => key > start.as_str(),
+            Bound::Unbounded       => true
+        }
+    }
+
+    fn before_end(&self, key: &str) -> bool {
+        match &self.end {
+            Bound::Included(end) => key <= end.as_str(),
+            Bound::Excluded(end) => key < end.as_str(),
+            Bound::Unbounded     => true
+        }
+    }
+}
+
+impl<'a> Iterator for Stream<'a> {
+    type Item = (String, u64);
+
+    fn next(&mut self) -> Option<(String, u64)> {
+        while let Some((prefix, state, value, filter_state)) = self.stack.pop() {
+            // The keys come in sorted order and every later key starts with a larger
+            // prefix, so nothing after the end of the range can be in it
+            if !self.before_end(&prefix) {
+                self.stack.clear();
+                return None;
+            }
+
+            let transitions: Vec<&Transition<char>> = self.map.dfa.transitions_from(state).collect();
+
+            for transition in transitions.into_iter().rev() {
+                let next_filter_state = match &self.filter {
+                    Some((dfa, live_states)) => {
+                        match filter_state.and_then(|filter_state| dfa.reachable(filter_state, transition.label)) {
+                            Some(next) if live_states.contains(&next) => Some(next),
+                            _ => continue
+                        }
+                    },
+                    None => None
+                };
+
+                let mut key = prefix.clone();
+                key.push(transition.label);
+
+                if !self.can_reach_start(&key) {
+                    continue;
+                }
+
+                let output = self.map.outputs[&(state, transition.label)];
+                self.stack.push((key, transition.to, value + output, next_filter_state));
+            }
+
+            let filter_accepts = match &self.filter {
+                Some((dfa, _)) => filter_state.is_some_and(|state| dfa.final_states.contains(&state)),
+                None           => true
+            };
+
+            if let Some(final_output) = self.map.final_outputs.get(&state) {
+                if filter_accepts && self.after_start(&prefix) {
+                    return Some((prefix, value + final_output));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outputs_are_pushed_to_the_front() {
+        let map = FstMap::from_sorted(vec![("mon", 2), ("tues", 3), ("tuesday", 5)]).unwrap();
+
+        // m -2-> o -> n and t -3-> u -> e -> s -> (final) -> d -2-> a -> y
+        assert_eq!(map.outputs[&(0, 'm')], 2);
+        assert_eq!(map.outputs[&(0, 't')], 3);
+        assert_eq!(map.outputs.values().sum::<u64>(), 7);
+        assert_eq!(map.get("tues"), Some(3));
+        assert_eq!(map.get("tuesday"), Some(5));
+    }
+
+    #[test]
+    fn shares_suffixes() {
+        let map = FstMap::from_sorted(vec![("ab", 1), ("bb", 1)]).unwrap();
+
+        assert_eq!(map.dfa.states.len(), 3);
+        assert_eq!(map.get("ab"), Some(1));
+        assert_eq!(map.get("bb"), Some(1));
+
+        // The outputs live on the first edges, so the keys still share the states after them
+        let map = FstMap::from_sorted(vec![("ab", 1), ("bb", 2)]).unwrap();
+        assert_eq!(map.dfa.states.len(), 3);
+        assert_eq!(map.get("bb"), Some(2));
+    }
+
+    #[test]
+    fn prefix_keys() {
+        let map = FstMap::from_sorted(vec![("", 7), ("a", 1), ("ab", 10), ("abc", 0)]).unwrap();
+
+        assert_eq!(map.get(""), Some(7));
+        assert_eq!(map.get("a"), Some(1));
+        assert_eq!(map.get("ab"), Some(10));
+        assert_eq!(map.get("abc"), Some(0));
+        assert_eq!(map.get("abcd"), None);
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![
+            ("".to_string(), 7),
+            ("a".to_string(), 1),
+            ("ab".to_string(), 10),
+            ("abc".to_string(), 0)
+        ]);
+    }
+
+    #[test]
+    fn order_errors() {
+        let mut builder = FstMapBuilder::new();
+
+        builder.insert("b", 1).unwrap();
+
+        assert!(builder.insert("b", 2).is_err());
+        assert_eq!(builder.insert("a", 2), Err(KeyOrderError { previous: "b".to_string(), key: "a".to_string() }));
+        assert_eq!(builder.finish().len(), 1);
+    }
+
+    #[test]
+    fn empty() {
+        let map = FstMapBuilder::new().finish();
+
+        assert!(map.is_empty());
+        assert_eq!(map.get(""), None);
+        assert_eq!(map.iter().count(), 0);
+    }
+}
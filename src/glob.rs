@@ -0,0 +1,482 @@
+use crate::nfa::NFA;
+use crate::dfa::DFA;
+use crate::expression_set::ExpressionSet;
+
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+
+const SEPARATOR: char = '/';
+// Every char of a class becomes a transition of its own, so wider classes are rejected
+// instead of building huge automata
+const MAX_CLASS_SIZE: usize = 4096;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GlobErrorKind {
+    UnclosedClass,
+    UnclosedBraces,
+    InvalidRange,
+    ClassTooLarge,
+    DanglingEscape
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GlobError {
+    pub kind: GlobErrorKind,
+    pub position: usize
+}
+
+impl GlobError {
+    fn new(kind: GlobErrorKind, position: usize) -> Self {
+        GlobError {
+            kind,
+            position
+        }
+    }
+}
+
+impl fmt::Display for GlobError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            GlobErrorKind::UnclosedClass  => write!(f, "'[' at position {} is never closed", self.position),
+            GlobErrorKind::UnclosedBraces => write!(f, "'{{' at position {} is never closed", self.position),
+            GlobErrorKind::InvalidRange   => write!(f, "range at position {} ends before it starts", self.position),
+            GlobErrorKind::ClassTooLarge  => write!(f, "class at position {} has more than {} chars", self.position, MAX_CLASS_SIZE),
+            GlobErrorKind::DanglingEscape => write!(f, "escape at position {} doesn't escape anything", self.position)
+        }
+    }
+}
+
+impl Error for GlobError {}
+
+// A glob that couldn't be parsed in a glob set
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GlobSetError {
+    pub index: usize,
+    pub error: GlobError
+}
+
+impl fmt::Display for GlobSetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "glob {}: {}", self.index, self.error)
+    }
+}
+
+impl Error for GlobSetError {}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Char(char),
+    // ? matches any char but the separator
+    AnyChar,
+    // * matches any number of chars without the separator
+    AnyChars,
+    // ** matches anything, including separators
+    AnyPath,
+    // **/ matches any number of whole directories
+    AnyDirectories,
+    Class { negated: bool, chars: BTreeSet<char> },
+    Alternatives(Vec<Vec<Token>>)
+}
+
+// Parses globs with the following syntax:
+//
+// ?          any char except '/'
+// *          any number of chars except '/'
+// **         any number of any chars. "**/" matches any number of whole directories.
+// [abc]      one of the chars. Ranges like [a-z] are allowed and [!abc] or [^abc]
+//            matches any char that is not listed. Classes never match '/'.
+// {foo,bar}  one of the comma separated globs, which can be nested
+// \c         the char c literally
+struct GlobParser {
+    chars: Vec<char>,
+    position: usize
+}
+
+impl GlobParser {
+    fn new(glob: &str) -> Self {
+        GlobParser {
+            chars: glob.chars().collect(),
+            position: 0
+        }
+    }
+
+    fn parse(mut self) -> Result<Vec<Token>, GlobError> {
+        self.parse_sequence(false)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).cloned()
+    }
+
+    // Parses tokens until the end of the glob or, inside braces, until ',' or '}'
+    fn parse_sequence(&mut self, in_braces: bool) -> Result<Vec<Token>, GlobError> {
+        let mut tokens = vec![];
+
+        while let Some(ch) = self.peek() {
+            let start = self.position;
+            self.position += 1;
+
+            let token = match ch {
+                ',' | '}' if in_braces => {
+                    self.position -= 1;
+                    break;
+                },
+                '?'  => Token::AnyChar,
+                '*'  => self.parse_stars(start),
+                '['  => self.parse_class(start)?,
+                '{'  => self.parse_alternatives(start)?,
+                '\\' => {
+                    let escaped = self.peek().ok_or_else(|| GlobError::new(GlobErrorKind::DanglingEscape, start))?;
+                    self.position += 1;
+                    Token::Char(escaped)
+                },
+                _    => Token::Char(ch)
+            };
+
+            tokens.push(token);
+        }
+
+        Ok(tokens)
+    }
+
+    fn parse_stars(&mut self, start: usize) -> Token {
+        if self.peek() != Some('*') {
+            return Token::AnyChars;
+        }
+
+        self.position += 1;
+
+        let starts_component = start == 0 || self.chars[start - 1] == SEPARATOR;
+
+        if starts_component && self.peek() == Some(SEPARATOR) {
+            self.position += 1;
+            Token::AnyDirectories
+        }
+        else {
+            Token::AnyPath
+        }
+    }
+
+    fn parse_class(&mut self, start: usize) -> Result<Token, GlobError> {
+        let unclosed = GlobError::new(GlobErrorKind::UnclosedClass, start);
+        let negated = matches!(self.peek(), Some('!') | Some('^'));
+        let mut chars = BTreeSet::new();
+
+        if negated {
+            self.position += 1;
+        }
+
+        // A ']' right at the start is a member of the class
+        let mut first = true;
+
+        loop {
+            let range_start = self.position;
+            let mut letter = self.peek().ok_or(unclosed)?;
+            self.position += 1;
+
+            if letter == ']' && !first {
+                break;
+            }
+
+            if letter == '\\' {
+                letter = self.peek().ok_or(unclosed)?;
+                self.position += 1;
+            }
+
+            first = false;
+
+            // A '-' right before the closing ']' is a member of the class
+            let is_range = self.peek() == Some('-') && self.chars.get(self.position + 1).is_some_and(|next| *next != ']');
+
+            if !is_range {
+                chars.insert(letter);
+                continue;
+            }
+
+            self.position += 1;
+            let mut end = self.peek().ok_or(unclosed)?;
+            self.position += 1;
+
+            if end == '\\' {
+                end = self.peek().ok_or(unclosed)?;
+                self.position += 1;
+            }
+
+            if end < letter {
+                return Err(GlobError::new(GlobErrorKind::InvalidRange, range_start));
+            }
+
+            // The range is counted before it's expanded. Surrogates are counted too, so
+            // this may overestimate it.
+            if chars.len() + (end as usize - letter as usize + 1) > MAX_CLASS_SIZE {
+                return Err(GlobError::new(GlobErrorKind::ClassTooLarge, start));
+            }
+
+            chars.extend(letter..=end);
+        }
+
+        Ok(Token::Class { negated, chars })
+    }
+
+    fn parse_alternatives(&mut self, start: usize) -> Result<Token, GlobError> {
+        let mut alternatives = vec![];
+
+        loop {
+            alternatives.push(self.parse_sequence(true)?);
+
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(Token::Alternatives(alternatives));
+                },
+                _ => return Err(GlobError::new(GlobErrorKind::UnclosedBraces, start))
+            }
+        }
+    }
+}
+
+// Adds the chars that the tokens mention to the set
+fn collect_chars(tokens: &[Token], chars: &mut BTreeSet<char>) {
+    for token in tokens {
+        match token {
+            Token::Char(ch)                => { chars.insert(*ch); },
+            Token::Class { chars: set, .. } => chars.extend(set),
+            Token::Alternatives(options)   => options.iter().for_each(|option| collect_chars(option, chars)),
+            _                              => ()
+        }
+    }
+}
+
+// The chars that the globs of a glob set are compiled with. Every char that is not
+// mentioned in any of the globs behaves the same way, so they are all replaced by
+// one char that the globs don't mention, which is then matched by the wildcards.
+#[derive(Debug)]
+struct Alphabet {
+    chars: BTreeSet<char>,
+    other: char
+}
+
+impl Alphabet {
+    fn new(globs: &[Vec<Token>]) -> Self {
+        let mut chars = set![SEPARATOR];
+
+        for tokens in globs {
+            collect_chars(tokens, &mut chars);
+        }
+
+        // The private use area is the least likely to be mentioned
+        let other = ('\u{E000}'..=char::MAX).find(|ch| !chars.contains(ch)).expect("Every char is mentioned");
+        chars.insert(other);
+
+        Alphabet {
+            chars,
+            other
+        }
+    }
+
+    fn symbol(&self, ch: char) -> char {
+        if self.chars.contains(&ch) { ch } else { self.other }
+    }
+
+    fn without_separator(&self) -> BTreeSet<char> {
+        self.chars.iter().filter(|ch| **ch != SEPARATOR).cloned().collect()
+    }
+
+    fn to_nfa(&self, tokens: &[Token]) -> NFA {
+        let mut nfa = NFA::empty_string();
+
+        for token in tokens {
+            nfa.concat(&self.token_to_nfa(token));
+        }
+
+        nfa
+    }
+
+    fn token_to_nfa(&self, token: &Token) -> NFA {
+        match token {
+            Token::Char(ch) => NFA::from_char(*ch),
+            Token::AnyChar  => NFA::from_chars(&self.without_separator()),
+            Token::AnyChars => {
+                let mut nfa = NFA::from_chars(&self.without_separator());
+                nfa.kleene();
+                nfa
+            },
+            Token::AnyPath => {
+                let mut nfa = NFA::from_chars(&self.chars);
+                nfa.kleene();
+                nfa
+            },
+            Token::AnyDirectories => {
+                let mut nfa = NFA::from_chars(&self.without_separator());
+                nfa.kleene();
+                nfa.concat(&NFA::from_char(SEPARATOR));
+                nfa.kleene();
+                nfa
+            },
+            Token::Class { negated: false, chars } => {
+                NFA::from_chars(&chars.iter().filter(|ch| **ch != SEPARATOR).cloned().collect())
+            },
+            Token::Class { negated: true, chars } => {
+                NFA::from_chars(&self.without_separator().difference(chars).cloned().collect())
+            },
+            Token::Alternatives(options) => {
+                let mut nfa = NFA::new();
+
+                for option in options {
+                    nfa.union(&self.to_nfa(option));
+                }
+
+                nfa
+            }
+        }
+    }
+}
+
+// A shell glob compiled into a minimized DFA
+#[derive(Debug)]
+pub struct Glob {
+    alphabet: Alphabet,
+    dfa: DFA
+}
+
+impl Glob {
+    // Panics if the glob is invalid. Use parse to handle the error instead.
+    pub fn new(glob: &str) -> Self {
+        match Glob::parse(glob) {
+            Ok(glob)   => glob,
+            Err(error) => panic!("Invalid glob {:?}: {}", glob, error)
+        }
+    }
+
+    pub fn parse(glob: &str) -> Result<Self, GlobError> {
+        let tokens = GlobParser::new(glob).parse()?;
+        let alphabet = Alphabet::new(std::slice::from_ref(&tokens));
+        let dfa = DFA::from_nfa(alphabet.to_nfa(&tokens)).minimize();
+
+        Ok(Glob {
+            alphabet,
+            dfa
+        })
+    }
+
+    // Returns whether the glob matches the whole path
+    pub fn matches(&self, path: &str) -> bool {
+        let mut state = match self.dfa.initial_state {
+            Some(state) => state,
+            None        => return false
+        };
+
+        for ch in path.chars() {
+            match self.dfa.reachable(state, self.alphabet.symbol(ch)) {
+                Some(next) => state = next,
+                None       => return false
+            }
+        }
+
+        self.dfa.final_states.contains(&state)
+    }
+
+    // Returns the minimized automaton of the glob. Chars that the glob doesn't
+    // mention are represented by a single char from the private use area.
+    pub fn dfa(&self) -> &DFA {
+        &self.dfa
+    }
+}
+
+// Matches a path against many globs at once with a single combined DFA
+#[derive(Debug)]
+pub struct GlobSet {
+    alphabet: Alphabet,
+    set: ExpressionSet
+}
+
+impl GlobSet {
+    // Panics if any of the globs is invalid. Use parse to handle the error instead.
+    pub fn new<S: AsRef<str>>(globs: &[S]) -> Self {
+        match GlobSet::parse(globs) {
+            Ok(set)    => set,
+            Err(error) => panic!("Invalid glob set: {}", error)
+        }
+    }
+
+    pub fn parse<S: AsRef<str>>(globs: &[S]) -> Result<Self, GlobSetError> {
+        let tokens = globs.iter()
+                          .enumerate()
+                          .map(|(index, glob)| GlobParser::new(glob.as_ref()).parse().map_err(|error| GlobSetError { index, error }))
+                          .collect::<Result<Vec<Vec<Token>>, GlobSetError>>()?;
+
+        let alphabet = Alphabet::new(&tokens);
+        let set = ExpressionSet::from_nfas(tokens.iter().map(|tokens| alphabet.to_nfa(tokens)).collect());
+
+        Ok(GlobSet {
+            alphabet,
+            set
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    // Returns the indices of the globs that match the path
+    pub fn matches(&self, path: &str) -> BTreeSet<usize> {
+        self.set.patterns_matched_in(self.set.final_state(path.chars().map(|ch| self.alphabet.symbol(ch))))
+    }
+
+    // Returns whether any of the globs matches the path
+    pub fn is_match(&self, path: &str) -> bool {
+        !self.matches(path).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(glob: &str) -> Result<Vec<Token>, GlobError> {
+        GlobParser::new(glob).parse()
+    }
+
+    #[test]
+    fn parse_tokens() {
+        assert_eq!(parse("a?\\*").unwrap(), vec![Token::Char('a'), Token::AnyChar, Token::Char('*')]);
+        assert_eq!(parse("**/a/**").unwrap(), vec![Token::AnyDirectories, Token::Char('a'), Token::Char('/'), Token::AnyPath]);
+        assert_eq!(parse("a**").unwrap(), vec![Token::Char('a'), Token::AnyPath]);
+        assert_eq!(parse("{a,}").unwrap(), vec![Token::Alternatives(vec![vec![Token::Char('a')], vec![]])]);
+        assert_eq!(parse("a,}").unwrap(), vec![Token::Char('a'), Token::Char(','), Token::Char('}')]);
+    }
+
+    #[test]
+    fn parse_classes() {
+        assert_eq!(parse("[a-c-]").unwrap(), vec![Token::Class { negated: false, chars: set!['a', 'b', 'c', '-'] }]);
+        assert_eq!(parse("[!]x]").unwrap(), vec![Token::Class { negated: true, chars: set![']', 'x'] }]);
+        assert_eq!(parse("[\\]]").unwrap(), vec![Token::Class { negated: false, chars: set![']'] }]);
+        assert!(parse("[\u{1000}-\u{1FFF}]").is_ok());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(parse("a[bc"), Err(GlobError::new(GlobErrorKind::UnclosedClass, 1)));
+        assert_eq!(parse("{a,{b}"), Err(GlobError::new(GlobErrorKind::UnclosedBraces, 0)));
+        assert_eq!(parse("[z-a]"), Err(GlobError::new(GlobErrorKind::InvalidRange, 1)));
+        assert_eq!(parse("a[\u{0}-\u{10FFFF}]"), Err(GlobError::new(GlobErrorKind::ClassTooLarge, 1)));
+        assert_eq!(parse("[a-z\u{100}-\u{1100}]"), Err(GlobError::new(GlobErrorKind::ClassTooLarge, 0)));
+        assert_eq!(parse("ab\\"), Err(GlobError::new(GlobErrorKind::DanglingEscape, 2)));
+    }
+
+    #[test]
+    fn unmentioned_chars_share_a_symbol() {
+        let glob = Glob::new("a*");
+
+        assert_eq!(glob.alphabet.chars, set!['/', 'a', '\u{E000}']);
+        assert_eq!(glob.alphabet.symbol('x'), '\u{E000}');
+        assert_eq!(glob.alphabet.symbol('a'), 'a');
+        assert_eq!(Alphabet::new(&[vec![Token::Char('\u{E000}')]]).other, '\u{E001}');
+    }
+}
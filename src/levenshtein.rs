@@ -0,0 +1,188 @@
+use crate::nfa::NFA;
+use crate::dfa::DFA;
+use crate::expression::Expression;
+use crate::transition::Transition;
+
+use std::collections::BTreeSet;
+
+// Accepts the strings that are at most max_distance edits away from a word. An edit
+// inserts, deletes or replaces a single char.
+//
+// The automaton has a state (i, e) for having matched the first i chars of the word
+// with e edits. Reading the next char of the word moves to (i + 1, e), while any char
+// can be inserted with (i, e + 1) or replace the next char with (i + 1, e + 1).
+// The next char can also be deleted by an epsilon transition to (i + 1, e + 1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevenshteinAutomaton {
+    word: Vec<char>,
+    max_distance: usize
+}
+
+impl LevenshteinAutomaton {
+    pub fn new(word: &str, max_distance: usize) -> Self {
+        LevenshteinAutomaton {
+            word: word.chars().collect(),
+            max_distance
+        }
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        self.distance(text).is_some()
+    }
+
+    // Returns the edit distance between the text and the word or None if it's
+    // larger than max_distance.
+    //
+    // The automaton is simulated without building it. Because (i, e) accepts everything
+    // that (i, e + 1) accepts, only the fewest edits for each i have to be followed.
+    pub fn distance(&self, text: &str) -> Option<usize> {
+        let too_many = self.max_distance + 1;
+
+        // The fewest edits for each position in the word, deleting the chars before it
+        let mut edits: Vec<usize> = (0..=self.word.len()).map(|i| i.min(too_many)).collect();
+
+        for ch in text.chars() {
+            let mut next = Vec::with_capacity(edits.len());
+            next.push((edits[0] + 1).min(too_many));
+
+            for i in 1..edits.len() {
+                let read = edits[i - 1] + if self.word[i - 1] == ch { 0 } else { 1 };
+                let inserted = edits[i] + 1;
+                let deleted = next[i - 1] + 1;
+
+                next.push(read.min(inserted).min(deleted).min(too_many));
+            }
+
+            // No state is left alive
+            if next.iter().all(|edits| *edits == too_many) {
+                return None;
+            }
+
+            edits = next;
+        }
+
+        Some(edits[self.word.len()]).filter(|edits| *edits <= self.max_distance)
+    }
+
+    fn state(&self, position: usize, edits: usize) -> u32 {
+        (position * (self.max_distance + 1) + edits) as u32
+    }
+
+    // Builds the automaton with the given chars standing for "any char"
+    pub(crate) fn to_nfa(&self, alphabet: &BTreeSet<char>) -> NFA {
+        let mut nfa = NFA::new();
+
+        nfa.alphabet = alphabet.iter().chain(&self.word).cloned().collect();
+
+        for position in 0..=self.word.len() {
+            for edits in 0..=self.max_distance {
+                let state = self.state(position, edits);
+                nfa.states.insert(state);
+
+                if position == self.word.len() {
+                    nfa.final_states.insert(state);
+                }
+                else {
+                    nfa.transitions.insert(Transition::new(state, Some(self.word[position]), self.state(position + 1, edits)));
+                }
+
+                if edits == self.max_distance {
+                    continue;
+                }
+
+                for letter in alphabet {
+                    nfa.transitions.insert(Transition::new(state, Some(*letter), self.state(position, edits + 1)));
+
+                    if position < self.word.len() {
+                        nfa.transitions.insert(Transition::new(state, Some(*letter), self.state(position + 1, edits + 1)));
+                    }
+                }
+
+                if position < self.word.len() {
+                    nfa.transitions.insert(Transition::new(state, None, self.state(position + 1, edits + 1)));
+                }
+            }
+        }
+
+        nfa.initial_states.insert(self.state(0, 0));
+        nfa.counter.value = nfa.states.len() as u32;
+
+        nfa
+    }
+
+    // Returns an expression that matches the strings of the expression that are within
+    // max_distance of the word. Its strings are the "did you mean" suggestions for the
+    // word when the expression is a dictionary.
+    pub fn intersect(&self, expression: &Expression) -> Expression {
+        let dictionary = expression.dfa();
+        let automaton = DFA::from_nfa(self.to_nfa(&dictionary.alphabet));
+
+        Expression::from_dfa(automaton.intersection(dictionary).minimize())
+    }
+
+    // Returns the strings of the expression that are within max_distance of the word
+    // together with their distance, closest first
+    pub fn suggestions(&self, expression: &Expression) -> Vec<(String, usize)> {
+        // The strings within a finite distance of a word are finitely many
+        let mut res: Vec<(String, usize)> = self.intersect(expression)
+                                                .strings()
+                                                .filter_map(|string| self.distance(&string).map(|distance| (string, distance)))
+                                                .collect();
+
+        res.sort_by_key(|(_, distance)| *distance);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        let automaton = LevenshteinAutomaton::new("kitten", 3);
+
+        assert_eq!(automaton.distance("kitten"), Some(0));
+        assert_eq!(automaton.distance("sitten"), Some(1));
+        assert_eq!(automaton.distance("sittin"), Some(2));
+        assert_eq!(automaton.distance("sitting"), Some(3));
+        assert_eq!(automaton.distance("kit"), Some(3));
+        assert_eq!(automaton.distance("ki"), None);
+        assert_eq!(automaton.distance("xxxxxxx"), None);
+    }
+
+    #[test]
+    fn distance_of_empty_strings() {
+        assert_eq!(LevenshteinAutomaton::new("", 2).distance("ab"), Some(2));
+        assert_eq!(LevenshteinAutomaton::new("", 0).distance(""), Some(0));
+        assert_eq!(LevenshteinAutomaton::new("ab", 1).distance(""), None);
+    }
+
+    #[test]
+    fn nfa_agrees_with_distance() {
+        let automaton = LevenshteinAutomaton::new("abc", 1);
+        let dfa = DFA::from_nfa(automaton.to_nfa(&set!['a', 'b', 'c', 'd']));
+
+        for text in &["abc", "ab", "bc", "ac", "abcd", "dabc", "adc", "bac", "a", "abcdd", ""] {
+            assert_eq!(dfa.matches(text), automaton.matches(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn nfa_states() {
+        let nfa = LevenshteinAutomaton::new("a", 1).to_nfa(&set!['b']);
+
+        assert_eq!(nfa.alphabet, set!['a', 'b']);
+        assert_eq!(nfa.states, set![0, 1, 2, 3]);
+        assert_eq!(nfa.initial_states, set![0]);
+        assert_eq!(nfa.final_states, set![2, 3]);
+        assert_eq!(nfa.transitions, set![
+            Transition::new(0, Some('a'), 2),
+            Transition::new(0, Some('b'), 1),
+            Transition::new(0, Some('b'), 3),
+            Transition::new(0, None, 3),
+            Transition::new(1, Some('a'), 3),
+            Transition::new(2, Some('b'), 3)
+        ]);
+    }
+}
@@ -0,0 +1,235 @@
+use crate::expression_set::{ExpressionSet, PatternError};
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+// A rule of the lexer. Text matched by a skip rule, like whitespace or comments,
+// doesn't produce tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule<K> {
+    kind: Option<K>,
+    pattern: String
+}
+
+impl<K> Rule<K> {
+    pub fn token(kind: K, pattern: &str) -> Self {
+        Rule {
+            kind: Some(kind),
+            pattern: pattern.to_string()
+        }
+    }
+
+    pub fn skip(pattern: &str) -> Self {
+        Rule {
+            kind: None,
+            pattern: pattern.to_string()
+        }
+    }
+}
+
+// The span is the byte range of the token's text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<K> {
+    pub kind: K,
+    pub span: Range<usize>
+}
+
+// No rule matches the text at the byte position, which starts with the char
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LexError {
+    pub position: usize,
+    pub unexpected: char
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unexpected {:?} at position {}", self.unexpected, self.position)
+    }
+}
+
+impl Error for LexError {}
+
+// Splits a text into tokens with an ordered list of rules. The patterns of all rules are
+// compiled into a single automaton. At each position the longest match wins and if more
+// than one rule matches it, the one that comes first in the list wins.
+#[derive(Debug)]
+pub struct Lexer<K> {
+    set: ExpressionSet,
+    kinds: Vec<Option<K>>
+}
+
+impl<K: Clone> Lexer<K> {
+    // Panics if any of the patterns is invalid. Use parse to handle the error instead.
+    pub fn new(rules: Vec<Rule<K>>) -> Self {
+        match Lexer::parse(rules) {
+            Ok(lexer)  => lexer,
+            Err(error) => panic!("Invalid lexer rule: {}", error)
+        }
+    }
+
+    // The index of the error is the index of the rule with the invalid pattern
+    pub fn parse(rules: Vec<Rule<K>>) -> Result<Self, PatternError> {
+        let patterns: Vec<&str> = rules.iter().map(|rule| rule.pattern.as_str()).collect();
+        let set = ExpressionSet::parse(&patterns)?;
+
+        Ok(Lexer {
+            set,
+            kinds: rules.into_iter().map(|rule| rule.kind).collect()
+        })
+    }
+
+    // Returns an iterator over the tokens of the text. When no rule matches at a position,
+    // an error is returned and lexing continues from the next char.
+    pub fn tokens<'a>(&'a self, text: &'a str) -> Tokens<'a, K> {
+        Tokens {
+            lexer: self,
+            text,
+            position: 0
+        }
+    }
+
+    // Returns all tokens of the text or the first error
+    pub fn tokenize(&self, text: &str) -> Result<Vec<Token<K>>, LexError> {
+        self.tokens(text).collect()
+    }
+
+    // Returns the length in bytes and the rule of the longest non-empty match
+    // at the start of the text
+    fn longest_match(&self, text: &str) -> Option<(usize, usize)> {
+        let dfa = &self.set.dfa;
+        let mut state = dfa.initial_state?;
+        let mut res = None;
+
+        for (index, ch) in text.char_indices() {
+            state = match dfa.reachable(state, ch).filter(|state| self.set.live_states.contains(state)) {
+                Some(state) => state,
+                None        => break
+            };
+
+            // The patterns are sorted by their index, so the first one has the highest priority
+            if let Some(rule) = self.set.matched_patterns.get(&state).and_then(|rules| rules.iter().next()) {
+                res = Some((index + ch.len_utf8(), *rule));
+            }
+        }
+
+        res
+    }
+}
+
+pub struct Tokens<'a, K> {
+    lexer: &'a Lexer<K>,
+    text: &'a str,
+    position: usize
+}
+
+impl<'a, K: Clone> Iterator for Tokens<'a, K> {
+    type Item = Result<Token<K>, LexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position < self.text.len() {
+            let rest = &self.text[self.position..];
+            let start = self.position;
+
+            match self.lexer.longest_match(rest) {
+                Some((length, rule)) => {
+                    self.position += length;
+
+                    if let Some(kind) = &self.lexer.kinds[rule] {
+                        return Some(Ok(Token { kind: kind.clone(), span: start..self.position }));
+                    }
+                },
+                None => {
+                    // Checked by the loop condition
+                    let unexpected = rest.chars().next().unwrap();
+                    self.position += unexpected.len_utf8();
+
+                    return Some(Err(LexError { position: start, unexpected }));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Kind {
+        If,
+        Identifier,
+        Number,
+        Equals,
+        DoubleEquals
+    }
+
+    fn lexer() -> Lexer<Kind> {
+        Lexer::new(vec![
+            Rule::token(Kind::If, "if"),
+            Rule::token(Kind::Identifier, "(a|b|f|i|x)+"),
+            Rule::token(Kind::Number, "(0|1|2|3)+"),
+            Rule::token(Kind::DoubleEquals, "=="),
+            Rule::token(Kind::Equals, "="),
+            Rule::skip(" +")
+        ])
+    }
+
+    fn kinds(text: &str) -> Vec<Kind> {
+        lexer().tokenize(text).unwrap().into_iter().map(|token| token.kind).collect()
+    }
+
+    #[test]
+    fn longest_match_wins() {
+        assert_eq!(kinds("iff"), vec![Kind::Identifier]);
+        assert_eq!(kinds("x==12"), vec![Kind::Identifier, Kind::DoubleEquals, Kind::Number]);
+        assert_eq!(kinds("x===1"), vec![Kind::Identifier, Kind::DoubleEquals, Kind::Equals, Kind::Number]);
+    }
+
+    #[test]
+    fn earlier_rule_wins_ties() {
+        assert_eq!(kinds("if"), vec![Kind::If]);
+        assert_eq!(kinds("if x"), vec![Kind::If, Kind::Identifier]);
+    }
+
+    #[test]
+    fn spans_skip_whitespace() {
+        assert_eq!(lexer().tokenize(" ab  = 10 ").unwrap(), vec![
+            Token { kind: Kind::Identifier, span: 1..3 },
+            Token { kind: Kind::Equals, span: 5..6 },
+            Token { kind: Kind::Number, span: 7..9 }
+        ]);
+        assert_eq!(lexer().tokenize("   ").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn errors_and_recovery() {
+        let lexer = lexer();
+
+        assert_eq!(lexer.tokenize("a é 1"), Err(LexError { position: 2, unexpected: 'é' }));
+
+        let tokens: Vec<_> = lexer.tokens("a é 1").collect();
+        assert_eq!(tokens, vec![
+            Ok(Token { kind: Kind::Identifier, span: 0..1 }),
+            Err(LexError { position: 2, unexpected: 'é' }),
+            Ok(Token { kind: Kind::Number, span: 5..6 })
+        ]);
+        assert_eq!(LexError { position: 2, unexpected: 'é' }.to_string(), "unexpected 'é' at position 2");
+    }
+
+    #[test]
+    fn empty_matches_are_ignored() {
+        let lexer = Lexer::new(vec![Rule::token(Kind::Number, "1*")]);
+
+        assert_eq!(lexer.tokenize("2"), Err(LexError { position: 0, unexpected: '2' }));
+    }
+
+    #[test]
+    fn invalid_rule() {
+        let error = Lexer::parse(vec![Rule::token(Kind::If, "if"), Rule::<Kind>::skip("(")]).unwrap_err();
+
+        assert_eq!(error.index, 1);
+    }
+}
@@ -1,10 +1,38 @@
+#![allow(clippy::upper_case_acronyms)]
+
 #[macro_use]
 mod helpers;
 mod counter;
-mod nfa;
-mod dfa;
 mod minimizer;
 mod determinizer;
 mod transition;
+mod parser;
+mod elimination;
+mod strings;
+mod trace;
+mod matcher;
+mod reader;
+mod expression_set;
+mod search;
+mod replace;
+mod captures;
+mod dot;
+mod binary;
+mod table;
+mod codegen;
+#[cfg(feature = "rand")]
+mod sampling;
+#[cfg(feature = "serde")]
+mod serialization;
 
 pub mod expression;
+pub mod nfa;
+pub mod dfa;
+pub mod lexer;
+pub mod glob;
+pub mod levenshtein;
+pub mod dawg;
+pub mod fst;
+pub mod transducer;
+#[cfg(feature = "ffi")]
+pub mod ffi;
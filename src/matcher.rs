@@ -0,0 +1,168 @@
+use crate::dfa::DFA;
+
+use std::collections::BTreeSet;
+
+// Runs the automaton over input that arrives in pieces. Only the current state
+// is carried between the pieces, so nothing of the input is buffered.
+#[derive(Debug, Clone)]
+pub struct Matcher<'a> {
+    dfa: &'a DFA,
+    live_states: BTreeSet<u32>,
+    // None if the automaton was stuck on a char without a transition
+    state: Option<u32>
+}
+
+// The position of a matcher that can be restored later. Unlike cloning the
+// matcher, taking a snapshot doesn't allocate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot {
+    state: Option<u32>
+}
+
+impl<'a> Matcher<'a> {
+    pub(crate) fn new(dfa: &'a DFA) -> Self {
+        Matcher {
+            dfa,
+            live_states: dfa.live_states(),
+            state: dfa.initial_state
+        }
+    }
+
+    pub fn feed(&mut self, chunk: &str) {
+        for ch in chunk.chars() {
+            if self.is_dead() {
+                return;
+            }
+
+            self.feed_char(ch);
+        }
+    }
+
+    pub fn feed_char(&mut self, ch: char) {
+        self.state = self.state.and_then(|state| self.dfa.reachable(state, ch));
+    }
+
+    // Returns whether everything fed so far is matched
+    pub fn is_accepting(&self) -> bool {
+        self.state.is_some_and(|state| self.dfa.final_states.contains(&state))
+    }
+
+    // Returns whether nothing that is fed from now on can lead to a match
+    pub fn is_dead(&self) -> bool {
+        !self.state.is_some_and(|state| self.live_states.contains(&state))
+    }
+
+    // Returns the state of the automaton or None if it got stuck on a char
+    // that is not in the alphabet
+    pub fn state(&self) -> Option<u32> {
+        self.state
+    }
+
+    // Forgets everything fed so far
+    pub fn reset(&mut self) {
+        self.state = self.dfa.initial_state;
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            state: self.state
+        }
+    }
+
+    // Goes back to a snapshot that was taken from a matcher of the same expression
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        self.state = snapshot.state;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transition::Transition;
+
+    // Matches "ab" over the alphabet {a, b}
+    fn dfa() -> DFA {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0, 1, 2, 3];
+        dfa.counter.value = 4;
+
+        dfa.initial_state = Some(1);
+        dfa.final_states = set![3];
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 0),
+            Transition::new(0, 'b', 0),
+            Transition::new(1, 'a', 2),
+            Transition::new(1, 'b', 0),
+            Transition::new(2, 'a', 0),
+            Transition::new(2, 'b', 3),
+            Transition::new(3, 'a', 0),
+            Transition::new(3, 'b', 0)
+        ];
+
+        dfa
+    }
+
+    #[test]
+    fn feed_chars() {
+        let dfa = dfa();
+        let mut matcher = Matcher::new(&dfa);
+
+        assert!(!matcher.is_accepting());
+        assert!(!matcher.is_dead());
+
+        matcher.feed_char('a');
+        assert_eq!(matcher.state(), Some(2));
+
+        matcher.feed_char('b');
+        assert!(matcher.is_accepting());
+
+        matcher.feed_char('b');
+        assert!(!matcher.is_accepting());
+        assert!(matcher.is_dead());
+        assert_eq!(matcher.state(), Some(0));
+    }
+
+    #[test]
+    fn stuck_on_unknown_char() {
+        let dfa = dfa();
+        let mut matcher = Matcher::new(&dfa);
+
+        matcher.feed("ac");
+        assert!(matcher.is_dead());
+        assert_eq!(matcher.state(), None);
+
+        matcher.reset();
+        assert_eq!(matcher.state(), Some(1));
+    }
+
+    #[test]
+    fn snapshots() {
+        let dfa = dfa();
+        let mut matcher = Matcher::new(&dfa);
+
+        matcher.feed("a");
+        let snapshot = matcher.snapshot();
+        let copy = matcher.clone();
+
+        matcher.feed("a");
+        assert!(matcher.is_dead());
+
+        matcher.restore(snapshot);
+        matcher.feed("b");
+        assert!(matcher.is_accepting());
+        assert_eq!(copy.state(), Some(2));
+    }
+
+    #[test]
+    fn empty_automaton() {
+        let dfa = DFA::new();
+        let mut matcher = Matcher::new(&dfa);
+
+        matcher.feed("");
+        assert!(matcher.is_dead());
+        assert!(!matcher.is_accepting());
+    }
+}
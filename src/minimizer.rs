@@ -60,7 +60,7 @@ impl Minimizer {
         // stop the iteration once we find the initial group.
         for (group_id, group) in &groups {
             // If the group has initial states, it will be initial in the new automaton
-            if group.iter().any(|(&state, _)| self.dfa.initial_state.map_or(false, |x| x == state)) {
+            if group.iter().any(|(&state, _)| self.dfa.initial_state == Some(state)) {
                 self.dfa.initial_state = Some(*group_id);
                 break;
             }
@@ -177,7 +177,7 @@ impl Minimizer {
             prev_groups_with_transitions = self.fill_group_transitions(prev_groups_with_transitions);
 
             for group in prev_groups_with_transitions.values() {
-                let states_with_same_transitions = Minimizer::find_states_with_same_transitions(&group);
+                let states_with_same_transitions = Minimizer::find_states_with_same_transitions(group);
 
                 for states in states_with_same_transitions.values() {
                     let mut new_group = BTreeMap::new();
@@ -1,20 +1,34 @@
 use crate::transition::Transition;
 use crate::counter::Counter;
 
-use std::iter::Peekable;
-use std::str::Chars;
+use crate::parser::{Parser, ParseError};
+use crate::dot::DotBuilder;
+use crate::dfa::write_table;
 
 use std::collections::BTreeSet;
+use std::fmt;
 
-#[derive(Debug)]
-pub(crate) struct NFA {
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+// When deserializing, the automaton is first read into NFAData and then validated
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "crate::serialization::NFAData"))]
+pub struct NFA {
     pub(crate) alphabet: BTreeSet<char>,
     pub(crate) states: BTreeSet<u32>,
     pub(crate) transitions: BTreeSet<Transition<Option<char>>>,
     pub(crate) final_states: BTreeSet<u32>,
     pub(crate) initial_states: BTreeSet<u32>,
 
+    // The states where each capture group starts and ends, in the order of their '('.
+    // Only the parser fills them in and only when it's asked to mark the groups.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) groups: Vec<(u32, u32)>,
+
     // Counter to track what the next state's id will be
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) counter: Counter
 }
 
@@ -26,10 +40,43 @@ impl NFA {
             transitions: BTreeSet::new(),
             final_states: BTreeSet::new(),
             initial_states: BTreeSet::new(),
+            groups: vec![],
             counter: Counter::new()
         }
     }
 
+    // Returns an automaton that matches only the empty string
+    pub(crate) fn empty_string() -> Self {
+        let mut nfa = NFA::new();
+        let state = nfa.counter.tick();
+
+        nfa.states.insert(state);
+        nfa.initial_states.insert(state);
+        nfa.final_states.insert(state);
+
+        nfa
+    }
+
+    // Returns an automaton that matches any one of the letters
+    pub(crate) fn from_chars(letters: &BTreeSet<char>) -> Self {
+        let mut nfa = NFA::new();
+        let state1 = nfa.counter.tick();
+        let state2 = nfa.counter.tick();
+
+        nfa.alphabet = letters.clone();
+        nfa.states.insert(state1);
+        nfa.states.insert(state2);
+
+        for letter in letters {
+            nfa.transitions.insert(Transition::new(state1, Some(*letter), state2));
+        }
+
+        nfa.initial_states.insert(state1);
+        nfa.final_states.insert(state2);
+
+        nfa
+    }
+
     pub(crate) fn from_char(letter: char) -> Self {
         let mut nfa = NFA::new();
         let state1 = nfa.counter.tick();
@@ -80,65 +127,8 @@ impl NFA {
         nfa
     }
 
-    // You pass the current char that is being processed to this function and the
-    // chars iterator that is being iterated over. The function peeks into the next
-    // chars and decides how to handle the current char.
-    // For example if the next items in the iterator are normal letters, it returns
-    // an automaton that matches only the current letter. If, however, the next char is
-    // '?', it returns an automata that mathes both the current letter and it's absence,
-    // so it optionally matches the current letter.
-    fn from_chars_iterator(current_char: char, iterator: &mut Peekable<Chars>) -> NFA {
-        match iterator.peek() {
-            Some(&next) => {
-                match next {
-                    '?' => {
-                        iterator.next();
-                        NFA::from_optional_char(current_char)
-                    },
-                    '*' => {
-                        iterator.next();
-
-                        let mut automata = NFA::from_char(current_char);
-                        automata.kleene();
-                        automata
-                    },
-                    '+' => {
-                        iterator.next();
-                        NFA::from_plus_char(current_char)
-                    },
-                    _   => NFA::from_char(current_char)
-                }
-            },
-            None => NFA::from_char(current_char)
-        }
-    }
-
-    pub(crate) fn from_string(string: &str) -> Self {
-        let mut nfa = NFA::new();
-
-        // For each slice of the expression that is separated by the OR character.
-        // (for example "ab|ca" would handle "ab" and "ca" separately and then union
-        // the two automata)
-        for or_slice in string.split('|') {
-            let mut chars = or_slice.chars().peekable();
-            let mut current_nfa;
-
-            // We need to handle the first character separately, because otherwise the current NFA would
-            // always be empty and concatenating anything to it would result in an automaton that
-            // doesn't match anything.
-            current_nfa = match chars.next() {
-                Some(ch) => NFA::from_chars_iterator(ch, &mut chars),
-                None => NFA::new()
-            };
-
-            while let Some(ch) = chars.next() {
-                current_nfa.concat(&NFA::from_chars_iterator(ch, &mut chars));
-            }
-
-            nfa.union(&current_nfa);
-        }
-
-        nfa
+    pub fn from_string(string: &str) -> Result<Self, ParseError> {
+        Parser::new(string).parse()
     }
 
     pub(crate) fn union(&mut self, other: &NFA) {
@@ -150,6 +140,7 @@ impl NFA {
         self.initial_states = self.initial_states.union(&other.initial_states).cloned().collect();
         self.final_states = self.final_states.union(&other.final_states).cloned().collect();
         self.transitions = self.transitions.union(&other.transitions).cloned().collect();
+        self.groups.extend(other.groups.iter().cloned());
     }
 
     pub(crate) fn concat(&mut self, other: &NFA) {
@@ -160,6 +151,7 @@ impl NFA {
         self.states = self.states.union(&other.states).cloned().collect();
 
         self.transitions = self.transitions.union(&other.transitions).cloned().collect();
+        self.groups.extend(other.groups.iter().cloned());
 
         for f in &self.final_states {
             for i in &other.initial_states {
@@ -194,23 +186,134 @@ impl NFA {
         self.final_states = set![new_final_state];
     }
 
+    pub(crate) fn optional(&mut self) {
+        let new_initial_state = self.counter.tick();
+        let new_final_state = self.counter.tick();
+
+        self.states.insert(new_initial_state);
+        self.states.insert(new_final_state);
+
+        for f in &self.final_states {
+            self.transitions.insert(Transition::new(*f, None, new_final_state));
+        }
+
+        for i in &self.initial_states {
+            self.transitions.insert(Transition::new(new_initial_state, None, *i));
+        }
+
+        self.transitions.insert(Transition::new(new_initial_state, None, new_final_state));
+        self.initial_states = set![new_initial_state];
+        self.final_states = set![new_final_state];
+    }
+
+    pub(crate) fn plus(&mut self) {
+        for f in &self.final_states {
+            for i in &self.initial_states {
+                self.transitions.insert(Transition::new(*f, None, *i));
+            }
+        }
+    }
+
+    // Makes the whole automaton a capture group. It gets a new initial state where the
+    // group starts and a new final state where it ends, so that nothing inside the group
+    // leads back to its start. The group goes before the groups that are inside it.
+    pub(crate) fn capture_group(&mut self) {
+        let start_state = self.counter.tick();
+        let end_state = self.counter.tick();
+
+        self.states.insert(start_state);
+        self.states.insert(end_state);
+
+        for i in &self.initial_states {
+            self.transitions.insert(Transition::new(start_state, None, *i));
+        }
+
+        for f in &self.final_states {
+            self.transitions.insert(Transition::new(*f, None, end_state));
+        }
+
+        self.initial_states = set![start_state];
+        self.final_states = set![end_state];
+        self.groups.insert(0, (start_state, end_state));
+    }
+
     fn shift_states(&mut self, amount: u32) {
         self.states = self.states.iter().map(|s| s + amount).collect();
         self.initial_states = self.initial_states.iter().map(|s| s + amount).collect();
         self.final_states = self.final_states.iter().map(|s| s + amount).collect();
+        self.groups = self.groups.iter().map(|(start, end)| (start + amount, end + amount)).collect();
 
         self.transitions = self.transitions.iter().map(|t|
             Transition::new(t.from + amount, t.label, t.to + amount)
         ).collect();
     }
 
+    pub fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn transition_count(&self) -> usize {
+        self.transitions.len()
+    }
+
+    // Returns a Graphviz DOT description of the automaton
+    pub fn to_dot(&self) -> String {
+        let mut builder = DotBuilder::new("NFA");
+
+        for state in &self.states {
+            builder.state(*state, self.initial_states.contains(state), self.final_states.contains(state));
+        }
+
+        for transition in &self.transitions {
+            builder.transition(transition.from, transition.label, transition.to);
+        }
+
+        builder.build()
+    }
+
     // Returns the states that are reachable by a state
     // through a specific transition
     pub(crate) fn reachable(&self, start_state: u32, wanted_label: Option<char>) -> BTreeSet<u32> {
-        self.transitions.iter()
-                        .filter(|s| s.from == start_state && s.label == wanted_label)
-                        .map(|s| s.to)
-                        .collect()
+        let start = Transition::new(start_state, wanted_label, 0);
+        let end = Transition::new(start_state, wanted_label, u32::MAX);
+
+        self.transitions.range(start..=end).map(|t| t.to).collect()
+    }
+}
+
+// Prints the transition table with a row for each state and a column for each char of
+// the alphabet and for epsilon. Each cell has the states reachable with the column's label.
+// Initial states are marked with "->" and final states with "*".
+impl fmt::Display for NFA {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let labels: Vec<Option<char>> = self.alphabet.iter().map(|l| Some(*l)).chain(Some(None)).collect();
+        let mut rows = vec![];
+        let mut header = vec![String::new(), String::new()];
+        header.extend(labels.iter().map(|label| label.map_or("ε".to_string(), |l| l.to_string())));
+        rows.push(header);
+
+        for state in &self.states {
+            let mut marker = String::new();
+
+            if self.initial_states.contains(state) {
+                marker.push_str("->");
+            }
+
+            if self.final_states.contains(state) {
+                marker.push('*');
+            }
+
+            let mut row = vec![marker, state.to_string()];
+
+            for label in &labels {
+                let reachable: Vec<String> = self.reachable(*state, *label).iter().map(|s| s.to_string()).collect();
+                row.push(if reachable.is_empty() { "-".to_string() } else { reachable.join(",") });
+            }
+
+            rows.push(row);
+        }
+
+        write_table(f, &rows)
     }
 }
 
@@ -230,6 +333,31 @@ mod tests {
         assert_eq!(nfa.counter.value, 2);
     }
 
+    #[test]
+    fn create_empty_string() {
+        let nfa = NFA::empty_string();
+
+        assert_eq!(nfa.alphabet, BTreeSet::new());
+        assert_eq!(nfa.states, set![0]);
+        assert_eq!(nfa.initial_states, set![0]);
+        assert_eq!(nfa.final_states, set![0]);
+        assert_eq!(nfa.transitions, BTreeSet::new());
+    }
+
+    #[test]
+    fn create_from_chars() {
+        let nfa = NFA::from_chars(&set!['a', 'b']);
+
+        assert_eq!(nfa.alphabet, set!['a', 'b']);
+        assert_eq!(nfa.states, set![0, 1]);
+        assert_eq!(nfa.initial_states, set![0]);
+        assert_eq!(nfa.final_states, set![1]);
+        assert_eq!(nfa.transitions, set![
+            Transition::new(0, Some('a'), 1),
+            Transition::new(0, Some('b'), 1)
+        ]);
+    }
+
     #[test]
     fn create_from_optional_char() {
         let nfa = NFA::from_optional_char('a');
@@ -262,7 +390,7 @@ mod tests {
 
     #[test]
     fn create_from_plain_string() {
-        let nfa = NFA::from_string("abc");
+        let nfa = NFA::from_string("abc").unwrap();
 
         assert_eq!(nfa.alphabet, set!['a', 'b', 'c']);
         assert_eq!(nfa.states, set![0, 1, 2, 3, 4, 5]);
@@ -280,7 +408,7 @@ mod tests {
 
     #[test]
     fn create_from_string_with_optional_chars() {
-        let nfa = NFA::from_string("ab?");
+        let nfa = NFA::from_string("ab?").unwrap();
 
         assert_eq!(nfa.alphabet, set!['a', 'b']);
         assert_eq!(nfa.states, set![0, 1, 2, 3]);
@@ -297,7 +425,7 @@ mod tests {
 
     #[test]
     fn create_from_string_with_kleene_chars() {
-        let nfa = NFA::from_string("ca*");
+        let nfa = NFA::from_string("ca*").unwrap();
 
         assert_eq!(nfa.alphabet, set!['c', 'a']);
         assert_eq!(nfa.states, set![0, 1, 2, 3, 4, 5]);
@@ -317,7 +445,7 @@ mod tests {
 
     #[test]
     fn create_from_string_with_plus_chars() {
-        let nfa = NFA::from_string("a+b");
+        let nfa = NFA::from_string("a+b").unwrap();
 
         assert_eq!(nfa.alphabet, set!['a', 'b']);
         assert_eq!(nfa.states, set![0, 1, 2, 3]);
@@ -334,7 +462,7 @@ mod tests {
 
     #[test]
     fn create_from_string_with_or_chars() {
-        let nfa = NFA::from_string("a|b");
+        let nfa = NFA::from_string("a|b").unwrap();
 
         assert_eq!(nfa.alphabet, set!['a', 'b']);
         assert_eq!(nfa.states, set![0, 1, 2, 3]);
@@ -403,6 +531,75 @@ mod tests {
         assert_eq!(nfa.counter.value, 4);
     }
 
+    #[test]
+    fn optional_automata() {
+        let mut nfa = NFA::from_char('a');
+
+        nfa.optional();
+
+        assert_eq!(nfa.states, set![0, 1, 2, 3]);
+        assert_eq!(nfa.initial_states, set![2]);
+        assert_eq!(nfa.final_states, set![3]);
+        assert_eq!(nfa.transitions, set![
+            Transition::new(0, Some('a'), 1),
+            Transition::new(1, None, 3),
+            Transition::new(2, None, 0),
+            Transition::new(2, None, 3)
+        ]);
+        assert_eq!(nfa.counter.value, 4);
+    }
+
+    #[test]
+    fn plus_automata() {
+        let mut nfa = NFA::from_char('a');
+
+        nfa.plus();
+
+        assert_eq!(nfa.states, set![0, 1]);
+        assert_eq!(nfa.initial_states, set![0]);
+        assert_eq!(nfa.final_states, set![1]);
+        assert_eq!(nfa.transitions, set![
+            Transition::new(0, Some('a'), 1),
+            Transition::new(1, None, 0)
+        ]);
+        assert_eq!(nfa.counter.value, 2);
+    }
+
+    #[test]
+    fn display_table() {
+        let nfa = NFA::from_string("a|ab").unwrap();
+
+        assert_eq!(nfa.to_string(), concat!(
+            "     a b ε\n",
+            "   0 - 1 -\n",
+            " * 1 - - -\n",
+            "-> 2 3 - -\n",
+            "   3 - - 0\n",
+            "-> 4 5 - -\n",
+            " * 5 - - -\n"
+        ));
+    }
+
+    #[test]
+    fn to_dot() {
+        let nfa = NFA::from_string("ab?").unwrap();
+
+        assert_eq!(nfa.to_dot(), concat!(
+            "digraph NFA {\n",
+            "    rankdir = LR;\n",
+            "    start [shape = point];\n",
+            "    0 [shape = circle];\n",
+            "    1 [shape = doublecircle];\n",
+            "    2 [shape = circle];\n",
+            "    3 [shape = circle];\n",
+            "    start -> 2;\n",
+            "    0 -> 1 [label = \"b, ε\"];\n",
+            "    2 -> 3 [label = \"a\"];\n",
+            "    3 -> 0 [label = \"ε\"];\n",
+            "}\n"
+        ));
+    }
+
     #[test]
     fn shift_states() {
         let mut nfa = NFA::from_char('a');
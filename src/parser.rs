@@ -0,0 +1,433 @@
+use crate::nfa::NFA;
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    // An operator like '*' that doesn't follow a character or a group
+    MissingOperand(char),
+
+    // A '(' without a matching ')'
+    UnclosedGroup,
+
+    // A ')' without a matching '('
+    UnmatchedParenthesis,
+
+    // A '\' at the end of the expression
+    DanglingEscape,
+
+    // A "(?" that isn't followed by "<name>", where the name is made of ASCII letters,
+    // digits and '_' and doesn't start with a digit
+    InvalidGroupName,
+
+    // A group with the same name as an earlier one
+    DuplicateGroupName,
+
+    // A group inside more than MAX_NESTING other groups
+    NestingTooDeep
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+
+    // The index of the char in the expression at which the error was found
+    pub position: usize
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, position: usize) -> Self {
+        ParseError {
+            kind,
+            position
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::MissingOperand(operator) => write!(f, "operator '{}' at position {} doesn't follow anything", operator, self.position),
+            ParseErrorKind::UnclosedGroup            => write!(f, "group opened at position {} is never closed", self.position),
+            ParseErrorKind::UnmatchedParenthesis     => write!(f, "unmatched ')' at position {}", self.position),
+            ParseErrorKind::DanglingEscape           => write!(f, "escape at position {} doesn't escape anything", self.position),
+            ParseErrorKind::InvalidGroupName         => write!(f, "group at position {} has an invalid name", self.position),
+            ParseErrorKind::DuplicateGroupName       => write!(f, "group at position {} has the name of an earlier group", self.position),
+            ParseErrorKind::NestingTooDeep           => write!(f, "group at position {} is nested too deeply", self.position)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+// Chars that have a special meaning and have to be escaped with '\' to be matched literally
+pub(crate) const SPECIAL_CHARS: [char; 7] = ['|', '?', '*', '+', '(', ')', '\\'];
+
+// The number of groups that a group can be nested in. Each level of nesting takes a few
+// frames of recursion, so without a limit a deeply nested pattern would overflow the stack.
+pub const MAX_NESTING: usize = 250;
+
+// Builds an NFA from an expression with the following grammar:
+//
+// alternation := sequence ('|' sequence)*
+// sequence    := repetition*
+// repetition  := atom ('?' | '*' | '+')*
+// atom        := '(' name? ')' | '(' name? alternation ')' | '\' char | char
+// name        := '?' '<' (letter | '_') (letter | digit | '_')* '>'
+//
+// An empty sequence doesn't match anything, so "a|" is the same as "a"
+// and "" matches nothing. The empty group "()" matches only the empty string.
+// Every group is a capture group, numbered from 1 by its '(' from left to right.
+pub(crate) struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    // The number of groups around the current position
+    depth: usize,
+    // Whether the groups are marked in the automaton
+    captures: bool,
+    // The names of the groups that were parsed so far, in the order of their '('
+    names: Vec<Option<String>>
+}
+
+impl Parser {
+    pub(crate) fn new(string: &str) -> Self {
+        Parser {
+            chars: string.chars().collect(),
+            position: 0,
+            depth: 0,
+            captures: false,
+            names: vec![]
+        }
+    }
+
+    pub(crate) fn parse(mut self) -> Result<NFA, ParseError> {
+        self.parse_expression()
+    }
+
+    // Builds an NFA from an expression in the basic syntax that Expression::new takes.
+    // It has no groups and no escaping. The expression is split on '|' and every other
+    // char is matched literally, optionally followed by one '?', '*' or '+' that applies
+    // to it. An operator that doesn't follow a char is matched literally as well, so
+    // every string is a valid expression. For example "(a|b)" matches "(a" and "b)".
+    pub(crate) fn parse_basic(mut self) -> NFA {
+        let mut nfa = NFA::new();
+
+        loop {
+            let mut sequence: Option<NFA> = None;
+
+            while let Some(letter) = self.peek().filter(|letter| *letter != '|') {
+                self.position += 1;
+                let next = self.parse_char(letter);
+
+                match &mut sequence {
+                    Some(sequence) => sequence.concat(&next),
+                    None           => sequence = Some(next)
+                }
+            }
+
+            nfa.union(&sequence.unwrap_or_else(NFA::new));
+
+            if self.peek().is_none() {
+                return nfa;
+            }
+
+            self.position += 1;
+        }
+    }
+
+    // Same as parse, but the automaton has a start and an end state for each capture
+    // group. The names of the groups are returned too.
+    pub(crate) fn parse_captures(mut self) -> Result<(NFA, Vec<Option<String>>), ParseError> {
+        self.captures = true;
+        let nfa = self.parse_expression()?;
+
+        Ok((nfa, self.names))
+    }
+
+    fn parse_expression(&mut self) -> Result<NFA, ParseError> {
+        let nfa = self.parse_alternation()?;
+
+        match self.peek() {
+            Some(_) => Err(ParseError::new(ParseErrorKind::UnmatchedParenthesis, self.position)),
+            None    => Ok(nfa)
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).cloned()
+    }
+
+    // Handles each slice of the expression that is separated by the OR character.
+    // (for example "ab|ca" would handle "ab" and "ca" separately and then union
+    // the two automata)
+    fn parse_alternation(&mut self) -> Result<NFA, ParseError> {
+        let mut nfa = NFA::new();
+
+        loop {
+            nfa.union(&self.parse_sequence()?);
+
+            if self.peek() == Some('|') {
+                self.position += 1;
+            }
+            else {
+                return Ok(nfa);
+            }
+        }
+    }
+
+    fn parse_sequence(&mut self) -> Result<NFA, ParseError> {
+        // We need to handle the first repetition separately, because otherwise the current NFA would
+        // always be empty and concatenating anything to it would result in an automaton that
+        // doesn't match anything.
+        let mut nfa = match self.parse_repetition()? {
+            Some(nfa) => nfa,
+            None      => return Ok(NFA::new())
+        };
+
+        while let Some(next) = self.parse_repetition()? {
+            nfa.concat(&next);
+        }
+
+        Ok(nfa)
+    }
+
+    // Returns None if there is nothing more to parse in the current sequence
+    fn parse_repetition(&mut self) -> Result<Option<NFA>, ParseError> {
+        let start = self.position;
+
+        let mut nfa = match self.peek() {
+            None | Some('|') | Some(')') => return Ok(None),
+            Some(operator @ '?') | Some(operator @ '*') | Some(operator @ '+') => {
+                return Err(ParseError::new(ParseErrorKind::MissingOperand(operator), start));
+            },
+            Some('(') => {
+                if self.depth == MAX_NESTING {
+                    return Err(ParseError::new(ParseErrorKind::NestingTooDeep, start));
+                }
+
+                self.position += 1;
+                let name = self.parse_group_name(start)?;
+                self.names.push(name);
+
+                self.depth += 1;
+
+                let mut nfa = match self.peek() {
+                    Some(')') => NFA::empty_string(),
+                    _         => self.parse_alternation()?
+                };
+
+                self.depth -= 1;
+
+                if self.peek() != Some(')') {
+                    return Err(ParseError::new(ParseErrorKind::UnclosedGroup, start));
+                }
+
+                self.position += 1;
+
+                if self.captures {
+                    nfa.capture_group();
+                }
+
+                nfa
+            },
+            Some('\\') => {
+                let letter = self.chars.get(start + 1).cloned();
+                let letter = letter.ok_or_else(|| ParseError::new(ParseErrorKind::DanglingEscape, start))?;
+
+                self.position += 2;
+                self.parse_char(letter)
+            },
+            Some(letter) => {
+                self.position += 1;
+                self.parse_char(letter)
+            }
+        };
+
+        while let Some(operator) = self.peek() {
+            match operator {
+                '?' => nfa.optional(),
+                '*' => nfa.kleene(),
+                '+' => nfa.plus(),
+                _   => break
+            }
+
+            self.position += 1;
+        }
+
+        Ok(Some(nfa))
+    }
+
+    // Parses the "?<name>" after the '(' of a group at the start position, if there is one
+    fn parse_group_name(&mut self, start: usize) -> Result<Option<String>, ParseError> {
+        if self.peek() != Some('?') {
+            return Ok(None);
+        }
+
+        let invalid = ParseError::new(ParseErrorKind::InvalidGroupName, start);
+        self.position += 1;
+
+        if self.peek() != Some('<') {
+            return Err(invalid);
+        }
+
+        self.position += 1;
+        let name_start = self.position;
+
+        while self.peek().is_some_and(|ch| ch.is_ascii_alphanumeric() || ch == '_') {
+            self.position += 1;
+        }
+
+        let name: String = self.chars[name_start..self.position].iter().collect();
+
+        if self.peek() != Some('>') || name.is_empty() || name.starts_with(|ch: char| ch.is_ascii_digit()) {
+            return Err(invalid);
+        }
+
+        self.position += 1;
+
+        if self.names.iter().flatten().any(|other| *other == name) {
+            return Err(ParseError::new(ParseErrorKind::DuplicateGroupName, start));
+        }
+
+        Ok(Some(name))
+    }
+
+    // Peeks at the char after the letter and decides how to handle the letter.
+    // For example if the next char is a normal letter, it returns an automaton that
+    // matches only the current letter. If, however, the next char is '?', it returns
+    // an automaton that matches both the current letter and it's absence, so it
+    // optionally matches the current letter.
+    fn parse_char(&mut self, letter: char) -> NFA {
+        let nfa = match self.peek() {
+            Some('?') => NFA::from_optional_char(letter),
+            Some('*') => {
+                let mut automata = NFA::from_char(letter);
+                automata.kleene();
+                automata
+            },
+            Some('+') => NFA::from_plus_char(letter),
+            _         => return NFA::from_char(letter)
+        };
+
+        self.position += 1;
+        nfa
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transition::Transition;
+
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn parse_empty_group() {
+        let nfa = Parser::new("()").parse().unwrap();
+
+        assert_eq!(nfa.states, set![0]);
+        assert_eq!(nfa.initial_states, set![0]);
+        assert_eq!(nfa.final_states, set![0]);
+
+        let nfa = Parser::new("").parse().unwrap();
+        assert!(nfa.final_states.is_empty());
+    }
+
+    #[test]
+    fn parse_escaped_chars() {
+        let nfa = Parser::new("\\*\\\\").parse().unwrap();
+
+        assert_eq!(nfa.alphabet, set!['*', '\\']);
+        assert_eq!(nfa.transitions, set![
+            Transition::new(2, Some('*'), 3),
+            Transition::new(3, None, 0),
+            Transition::new(0, Some('\\'), 1)
+        ]);
+    }
+
+    #[test]
+    fn parse_group() {
+        let nfa = Parser::new("(ab)*").parse().unwrap();
+
+        assert_eq!(nfa.alphabet, set!['a', 'b']);
+        assert_eq!(nfa.states, set![0, 1, 2, 3, 4, 5]);
+        assert_eq!(nfa.initial_states, set![4]);
+        assert_eq!(nfa.final_states, set![5]);
+        assert_eq!(nfa.transitions, set![
+            Transition::new(2, Some('a'), 3),
+            Transition::new(3, None, 0),
+            Transition::new(0, Some('b'), 1),
+            Transition::new(1, None, 2),
+            Transition::new(1, None, 5),
+            Transition::new(4, None, 2),
+            Transition::new(4, None, 5)
+        ]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Parser::new("*a").parse().unwrap_err(), ParseError::new(ParseErrorKind::MissingOperand('*'), 0));
+        assert_eq!(Parser::new("a|+").parse().unwrap_err(), ParseError::new(ParseErrorKind::MissingOperand('+'), 2));
+        assert_eq!(Parser::new("a(b|c").parse().unwrap_err(), ParseError::new(ParseErrorKind::UnclosedGroup, 1));
+        assert_eq!(Parser::new("ab)").parse().unwrap_err(), ParseError::new(ParseErrorKind::UnmatchedParenthesis, 2));
+        assert_eq!(Parser::new("ab\\").parse().unwrap_err(), ParseError::new(ParseErrorKind::DanglingEscape, 2));
+        assert_eq!(Parser::new("a(?b)").parse().unwrap_err(), ParseError::new(ParseErrorKind::InvalidGroupName, 1));
+        assert_eq!(Parser::new("(?<1a>b)").parse().unwrap_err(), ParseError::new(ParseErrorKind::InvalidGroupName, 0));
+        assert_eq!(Parser::new("(?<>b)").parse().unwrap_err(), ParseError::new(ParseErrorKind::InvalidGroupName, 0));
+        assert_eq!(Parser::new("(?<a b>c)").parse().unwrap_err(), ParseError::new(ParseErrorKind::InvalidGroupName, 0));
+        assert_eq!(Parser::new("(?<a>b)(?<a>c)").parse().unwrap_err(), ParseError::new(ParseErrorKind::DuplicateGroupName, 7));
+    }
+
+    #[test]
+    fn parse_basic_syntax() {
+        let nfa = Parser::new("(a|*+)").parse_basic();
+
+        assert_eq!(nfa.alphabet, set!['(', 'a', '*', ')']);
+        assert_eq!(nfa.transitions, set![
+            Transition::new(6, Some('('), 7),
+            Transition::new(7, None, 4),
+            Transition::new(4, Some('a'), 5),
+            Transition::new(2, Some('*'), 3),
+            Transition::new(3, Some('*'), 3),
+            Transition::new(3, None, 0),
+            Transition::new(0, Some(')'), 1)
+        ]);
+
+        let nfa = Parser::new("ab\\|").parse_basic();
+        assert_eq!(nfa.alphabet, set!['a', 'b', '\\']);
+        assert_eq!(nfa.final_states.len(), 1);
+    }
+
+    #[test]
+    fn parse_deep_nesting() {
+        let nested = |depth: usize| format!("{}a{}", "(".repeat(depth), ")".repeat(depth));
+
+        assert!(Parser::new(&nested(MAX_NESTING)).parse_captures().is_ok());
+        assert_eq!(Parser::new(&nested(MAX_NESTING + 1)).parse().unwrap_err(), ParseError::new(ParseErrorKind::NestingTooDeep, MAX_NESTING));
+        assert_eq!(Parser::new(&nested(10_000)).parse().unwrap_err(), ParseError::new(ParseErrorKind::NestingTooDeep, MAX_NESTING));
+
+        // Groups one after another aren't nested
+        assert!(Parser::new(&"(a)".repeat(MAX_NESTING + 1)).parse().is_ok());
+    }
+
+    #[test]
+    fn parse_capture_groups() {
+        let (nfa, names) = Parser::new("(?<first>a)((b)|())").parse_captures().unwrap();
+
+        assert_eq!(names, vec![Some("first".to_string()), None, None, None]);
+        assert_eq!(nfa.groups.len(), 4);
+
+        // Every group gets a start and an end state of its own
+        let states: BTreeSet<u32> = nfa.groups.iter().flat_map(|(start, end)| vec![*start, *end]).collect();
+        assert_eq!(states.len(), 8);
+        assert_eq!(nfa.initial_states, set![nfa.groups[0].0]);
+        assert_eq!(nfa.final_states, set![nfa.groups[1].1]);
+
+        // Without captures, the groups only group
+        let nfa = Parser::new("(?<first>a)((b)|())").parse().unwrap();
+        assert!(nfa.groups.is_empty());
+        assert_eq!(nfa.alphabet, set!['a', 'b']);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::dfa::DFA;
+use crate::matcher::Matcher;
+use crate::search::LeftmostLongest;
+
+use std::io::{self, ErrorKind, Read};
+use std::ops::Range;
+use std::str;
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+// A UTF-8 encoded char is at most this long, so at most MAX_CHAR_LENGTH - 1 bytes
+// of an incomplete char are left over at the end of a buffer
+const MAX_CHAR_LENGTH: usize = 4;
+
+// Decodes the UTF-8 text of the reader with a fixed size buffer and calls f with each
+// char and its byte offset until f returns false. The bytes of a char that is split
+// between two reads are carried over to the next one.
+fn for_each_char<R: Read, F: FnMut(u64, char) -> bool>(mut reader: R, mut f: F) -> io::Result<()> {
+    let mut buffer = [0u8; BUFFER_SIZE];
+    let mut left_over = 0;
+    let mut offset = 0u64;
+
+    loop {
+        let read = match reader.read(&mut buffer[left_over..]) {
+            Ok(read) => read,
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error)
+        };
+
+        let length = left_over + read;
+
+        if read == 0 {
+            if left_over > 0 {
+                return Err(io::Error::new(ErrorKind::InvalidData, "stream ends in the middle of a UTF-8 char"));
+            }
+
+            return Ok(());
+        }
+
+        let valid_length = match str::from_utf8(&buffer[..length]) {
+            Ok(_) => length,
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(error) => return Err(io::Error::new(ErrorKind::InvalidData, error))
+        };
+
+        // The prefix was just checked to be valid UTF-8
+        let text = str::from_utf8(&buffer[..valid_length]).unwrap();
+
+        for (index, ch) in text.char_indices() {
+            if !f(offset + index as u64, ch) {
+                return Ok(());
+            }
+        }
+
+        offset += valid_length as u64;
+        left_over = length - valid_length;
+        debug_assert!(left_over < MAX_CHAR_LENGTH);
+        buffer.copy_within(valid_length..length, 0);
+    }
+}
+
+pub(crate) fn matches_reader<R: Read>(dfa: &DFA, reader: R) -> io::Result<bool> {
+    let mut matcher = Matcher::new(dfa);
+
+    for_each_char(reader, |_, ch| {
+        matcher.feed_char(ch);
+        !matcher.is_dead()
+    })?;
+
+    Ok(matcher.is_accepting())
+}
+
+// Returns the byte range of the first match in the reader's text like Matches does for a
+// str. Reading stops once no more text can change the match.
+pub(crate) fn find_in_reader<R: Read>(dfa: &DFA, reader: R) -> io::Result<Option<Range<u64>>> {
+    let live_states = dfa.live_states();
+    let mut search = match LeftmostLongest::new(dfa, &live_states, 0, None) {
+        Some(search) => search,
+        None         => return Ok(None)
+    };
+
+    for_each_char(reader, |_, ch| {
+        if !search.check() {
+            return false;
+        }
+
+        search.feed_char(ch);
+        true
+    })?;
+
+    Ok(search.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Returns the bytes one at a time to split every char between reads
+    struct ByteByByte<'a>(&'a [u8]);
+
+    impl<'a> Read for ByteByByte<'a> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                Some((first, rest)) if !buffer.is_empty() => {
+                    buffer[0] = *first;
+                    self.0 = rest;
+                    Ok(1)
+                },
+                _ => Ok(0)
+            }
+        }
+    }
+
+    fn chars<R: Read>(reader: R) -> io::Result<Vec<(u64, char)>> {
+        let mut res = vec![];
+        for_each_char(reader, |offset, ch| {
+            res.push((offset, ch));
+            true
+        })?;
+
+        Ok(res)
+    }
+
+    #[test]
+    fn chars_split_between_reads() {
+        let expected = vec![(0, 'a'), (1, 'é'), (3, '€'), (6, '😀'), (10, 'b')];
+
+        assert_eq!(chars("aé€😀b".as_bytes()).unwrap(), expected);
+        assert_eq!(chars(ByteByByte("aé€😀b".as_bytes())).unwrap(), expected);
+    }
+
+    #[test]
+    fn chars_across_buffers() {
+        let text = "é".repeat(BUFFER_SIZE);
+        let decoded = chars(text.as_bytes()).unwrap();
+
+        assert_eq!(decoded.len(), BUFFER_SIZE);
+        assert_eq!(decoded.last(), Some(&(2 * BUFFER_SIZE as u64 - 2, 'é')));
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        assert_eq!(chars(&b"ab\xffc"[..]).unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(chars(&b"ab\xc3"[..]).unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(chars(ByteByByte(b"ab\xc3")).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn stop_early() {
+        let mut seen = vec![];
+        for_each_char("abc".as_bytes(), |_, ch| {
+            seen.push(ch);
+            ch != 'b'
+        }).unwrap();
+
+        assert_eq!(seen, vec!['a', 'b']);
+    }
+}
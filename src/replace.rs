@@ -0,0 +1,113 @@
+use crate::captures::Captures;
+
+// What a match is replaced with by Expression::replace_all and replace_n.
+// It's implemented for strings and for closures that get the matched text.
+//
+// In a replacement string, $n and ${n} stand for the text of group n, where $0 is
+// the whole match, $name and ${name} for the text of the group with the name and
+// $$ for a single $. A group that is not part of the match is replaced by nothing.
+// The name after a $ is as long as possible, so "$1a" refers to a group named "1a"
+// and "${1}a" has to be used instead. A $ that doesn't refer to a group is kept
+// as it is.
+pub trait Replacer {
+    fn append_replacement(&mut self, captures: &Captures, res: &mut String);
+}
+
+impl Replacer for &str {
+    fn append_replacement(&mut self, captures: &Captures, res: &mut String) {
+        let mut rest = *self;
+
+        while let Some(index) = rest.find('$') {
+            res.push_str(&rest[..index]);
+            rest = &rest[index + 1..];
+
+            if let Some(after) = rest.strip_prefix('$') {
+                res.push('$');
+                rest = after;
+                continue;
+            }
+
+            let (name, after) = match rest.strip_prefix('{') {
+                Some(braced) => match braced.find('}') {
+                    Some(end) => (&braced[..end], &braced[end + 1..]),
+                    None      => ("", rest)
+                },
+                None => {
+                    let end = rest.find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_')).unwrap_or(rest.len());
+                    (&rest[..end], &rest[end..])
+                }
+            };
+
+            let group = match name.parse::<usize>() {
+                Ok(index) => Some(index).filter(|index| *index < captures.group_count()),
+                Err(_)    => captures.index_of(name)
+            };
+
+            match group {
+                Some(index) => res.push_str(captures.get(index).unwrap_or("")),
+                None        => {
+                    res.push('$');
+                    res.push_str(&rest[..rest.len() - after.len()]);
+                }
+            }
+
+            rest = after;
+        }
+
+        res.push_str(rest);
+    }
+}
+
+impl Replacer for String {
+    fn append_replacement(&mut self, captures: &Captures, res: &mut String) {
+        self.as_str().append_replacement(captures, res);
+    }
+}
+
+impl<F: FnMut(&str) -> T, T: AsRef<str>> Replacer for F {
+    fn append_replacement(&mut self, captures: &Captures, res: &mut String) {
+        res.push_str(self(captures.as_str()).as_ref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::Expression;
+
+    // Expands the replacement for the first match of the pattern in the text
+    fn expand(mut replacement: &str, pattern: &str, text: &str) -> String {
+        let expression = Expression::parse(pattern).unwrap();
+        let mut res = String::new();
+
+        replacement.append_replacement(&expression.captures(text).unwrap(), &mut res);
+        res
+    }
+
+    #[test]
+    fn references() {
+        assert_eq!(expand("<$0>", "ab", "ab"), "<ab>");
+        assert_eq!(expand("${0}0", "ab", "ab"), "ab0");
+        assert_eq!(expand("$$0 costs $", "ab", "ab"), "$0 costs $");
+        assert_eq!(expand("${0", "ab", "ab"), "${0");
+        assert_eq!(expand("no references", "ab", "ab"), "no references");
+    }
+
+    #[test]
+    fn group_references() {
+        assert_eq!(expand("$2-$1", "(a+)(b+)", "aab"), "b-aa");
+        assert_eq!(expand("${2}1", "(a+)(b+)", "aab"), "b1");
+        assert_eq!(expand("[$first]", "(?<first>a+)b", "aab"), "[aa]");
+        assert_eq!(expand("[${first}]", "(?<first>a+)b", "aab"), "[aa]");
+        assert_eq!(expand("<$1>", "(a)|b", "b"), "<>");
+    }
+
+    #[test]
+    fn references_to_missing_groups_are_kept() {
+        assert_eq!(expand("$1", "ab", "ab"), "$1");
+        assert_eq!(expand("${name}", "ab", "ab"), "${name}");
+        assert_eq!(expand("$2 ${3}", "(a)b", "ab"), "$2 ${3}");
+        assert_eq!(expand("$1a", "(a)b", "ab"), "$1a");
+        assert_eq!(expand("$99999999999999999999999", "(a)b", "ab"), "$99999999999999999999999");
+    }
+}
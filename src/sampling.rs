@@ -0,0 +1,219 @@
+use crate::dfa::DFA;
+
+use rand::Rng;
+
+use std::collections::{BTreeSet, BTreeMap};
+
+// Draws random accepted strings from a DFA
+pub(crate) struct Sampler<'a> {
+    dfa: &'a DFA,
+    live_states: BTreeSet<u32>
+}
+
+impl<'a> Sampler<'a> {
+    pub(crate) fn new(dfa: &'a DFA) -> Self {
+        Sampler {
+            dfa,
+            live_states: dfa.live_states()
+        }
+    }
+
+    // Returns a table where the element at index i maps each live state to the number
+    // of strings of length i that lead from it to a final state. Returns None if any
+    // of those numbers doesn't fit into an u128.
+    fn completions(&self, max_length: usize) -> Option<Vec<BTreeMap<u32, u128>>> {
+        let mut res = Vec::with_capacity(max_length + 1);
+
+        let accepted_empty: BTreeMap<u32, u128> = self.dfa.final_states.iter().map(|s| (*s, 1)).collect();
+        res.push(accepted_empty);
+
+        for length in 1..=max_length {
+            let mut current = BTreeMap::new();
+
+            for state in &self.live_states {
+                let mut count: u128 = 0;
+
+                for transition in self.dfa.transitions_from(*state) {
+                    if let Some(next_count) = res[length - 1].get(&transition.to) {
+                        count = count.checked_add(*next_count)?;
+                    }
+                }
+
+                if count > 0 {
+                    current.insert(*state, count);
+                }
+            }
+
+            res.push(current);
+        }
+
+        Some(res)
+    }
+
+    // Picks each accepted string of the given length with equal probability.
+    // Every step chooses the next character with probability proportional to the
+    // number of accepted strings that can still be reached after taking it.
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R, length: usize) -> Option<String> {
+        let completions = self.completions(length)?;
+        let mut current_state = self.dfa.initial_state?;
+        let mut res = String::with_capacity(length);
+
+        for remaining in (1..=length).rev() {
+            let total = *completions[remaining].get(&current_state)?;
+            let mut choice = rng.gen_range(0..total);
+
+            for transition in self.dfa.transitions_from(current_state) {
+                let count = completions[remaining - 1].get(&transition.to).cloned().unwrap_or(0);
+
+                if choice < count {
+                    res.push(transition.label);
+                    current_state = transition.to;
+                    break;
+                }
+
+                choice -= count;
+            }
+        }
+
+        if self.dfa.final_states.contains(&current_state) {
+            Some(res)
+        }
+        else {
+            None
+        }
+    }
+
+    // Walks the automaton by taking uniformly random transitions that don't lead into
+    // dead states. Every time the walk is in a final state, it stops with the given
+    // probability. The resulting strings are not uniformly distributed, but they are
+    // not limited to a specific length either. The probability has to be in (0, 1],
+    // because with 0 the walk may never stop.
+    pub(crate) fn random_walk<R: Rng + ?Sized>(&self, rng: &mut R, stop_probability: f64) -> Option<String> {
+        if !(stop_probability > 0.0 && stop_probability <= 1.0) {
+            return None;
+        }
+
+        let mut current_state = self.dfa.initial_state?;
+        let mut res = String::new();
+
+        if !self.live_states.contains(&current_state) {
+            return None;
+        }
+
+        loop {
+            let is_final = self.dfa.final_states.contains(&current_state);
+
+            if is_final && rng.gen_bool(stop_probability) {
+                return Some(res);
+            }
+
+            let next_transitions: Vec<_> = self.dfa.transitions_from(current_state)
+                                                   .filter(|t| self.live_states.contains(&t.to))
+                                                   .collect();
+
+            if next_transitions.is_empty() {
+                return Some(res);
+            }
+
+            let transition = next_transitions[rng.gen_range(0..next_transitions.len())];
+            res.push(transition.label);
+            current_state = transition.to;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transition::Transition;
+
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    // Accepts "a", "b" and all strings of the form "b(a|b)"
+    fn build_dfa() -> DFA {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0, 1, 2, 3, 4];
+        dfa.counter.value = 5;
+
+        dfa.initial_state = Some(1);
+        dfa.final_states = set![2, 3, 4];
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 0),
+            Transition::new(0, 'b', 0),
+            Transition::new(1, 'a', 2),
+            Transition::new(1, 'b', 3),
+            Transition::new(2, 'a', 0),
+            Transition::new(2, 'b', 0),
+            Transition::new(3, 'a', 4),
+            Transition::new(3, 'b', 4),
+            Transition::new(4, 'a', 0),
+            Transition::new(4, 'b', 0)
+        ];
+
+        dfa
+    }
+
+    #[test]
+    fn completions() {
+        let dfa = build_dfa();
+        let completions = Sampler::new(&dfa).completions(2).unwrap();
+
+        assert_eq!(completions[0], vec![(2, 1), (3, 1), (4, 1)].into_iter().collect());
+        assert_eq!(completions[1], vec![(1, 2), (3, 2)].into_iter().collect());
+        assert_eq!(completions[2], vec![(1, 2)].into_iter().collect());
+    }
+
+    #[test]
+    fn sample_is_uniform() {
+        let dfa = build_dfa();
+        let sampler = Sampler::new(&dfa);
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut found = BTreeMap::<String, u32>::new();
+
+        for _ in 0..1000 {
+            let string = sampler.sample(&mut rng, 2).unwrap();
+            *found.entry(string).or_insert(0) += 1;
+        }
+
+        assert_eq!(found.keys().cloned().collect::<Vec<_>>(), vec!["ba", "bb"]);
+        assert!(found.values().all(|count| *count > 400));
+    }
+
+    #[test]
+    fn sample_without_strings_of_length() {
+        let dfa = build_dfa();
+        let mut rng = StdRng::seed_from_u64(42);
+
+        assert_eq!(Sampler::new(&dfa).sample(&mut rng, 0), None);
+        assert_eq!(Sampler::new(&dfa).sample(&mut rng, 3), None);
+    }
+
+    #[test]
+    fn random_walk() {
+        let dfa = build_dfa();
+        let sampler = Sampler::new(&dfa);
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for _ in 0..100 {
+            let string = sampler.random_walk(&mut rng, 0.5).unwrap();
+            assert!(dfa.matches(&string));
+        }
+    }
+
+    #[test]
+    fn random_walk_stop_probability() {
+        let dfa = build_dfa();
+        let sampler = Sampler::new(&dfa);
+        let mut rng = StdRng::seed_from_u64(42);
+
+        assert!(sampler.random_walk(&mut rng, 1.0).is_some());
+
+        for probability in &[0.0, -0.5, 1.5, f64::NAN, f64::INFINITY] {
+            assert_eq!(sampler.random_walk(&mut rng, *probability), None);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use crate::dfa::DFA;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+// The search for the match that starts first and of those starting there, the longest
+// one. It's fed the text one char at a time. Every state that the substrings starting at
+// or after the start of the search lead to is followed together with the earliest start
+// that leads to it, because a later start in the same state can't give an earlier or
+// longer match. Once a match is found, only the states with a start that is not after it
+// are followed, until they can't match anymore. The memory used depends only on the
+// automaton and not on the text.
+pub(crate) struct LeftmostLongest<'a> {
+    dfa: &'a DFA,
+    live_states: &'a BTreeSet<u32>,
+    initial_state: u32,
+    current_states: BTreeMap<u32, u64>,
+    found: Option<Range<u64>>,
+    // The byte position after the chars that were fed so far
+    end: u64,
+    // An empty match at this position is skipped
+    skipped_empty_match: Option<u64>
+}
+
+impl<'a> LeftmostLongest<'a> {
+    // Returns None if the automaton can't match anything
+    pub(crate) fn new(dfa: &'a DFA, live_states: &'a BTreeSet<u32>, start: u64, skipped_empty_match: Option<u64>) -> Option<Self> {
+        let initial_state = dfa.initial_state.filter(|state| live_states.contains(state))?;
+
+        Some(LeftmostLongest {
+            dfa,
+            live_states,
+            initial_state,
+            current_states: BTreeMap::new(),
+            found: None,
+            end: start,
+            skipped_empty_match
+        })
+    }
+
+    // Takes the matches that end at the current position into account. Returns false
+    // once no char that follows can change the match that was found.
+    pub(crate) fn check(&mut self) -> bool {
+        // Matches that start later than one that was found don't matter
+        if self.found.is_none() {
+            self.current_states.entry(self.initial_state).or_insert(self.end);
+        }
+
+        let earliest = self.current_states
+                           .iter()
+                           .filter(|(state, start)| self.dfa.final_states.contains(state) && !(**start == self.end && self.skipped_empty_match == Some(self.end)))
+                           .map(|(_, start)| *start)
+                           .min();
+
+        match (earliest, &self.found) {
+            (Some(start), Some(range)) if start > range.start => {},
+            (Some(start), _) => self.found = Some(start..self.end),
+            (None, _)        => {}
+        }
+
+        if let Some(range) = &self.found {
+            self.current_states.retain(|_, start| *start <= range.start);
+        }
+
+        self.found.is_none() || !self.current_states.is_empty()
+    }
+
+    pub(crate) fn feed_char(&mut self, ch: char) {
+        let mut next_states = BTreeMap::new();
+
+        for (state, start) in &self.current_states {
+            if let Some(next) = self.dfa.reachable(*state, ch).filter(|next| self.live_states.contains(next)) {
+                let earliest = next_states.entry(next).or_insert(*start);
+                *earliest = (*earliest).min(*start);
+            }
+        }
+
+        self.current_states = next_states;
+        self.end += ch.len_utf8() as u64;
+    }
+
+    // Returns the match once the text has ended or check has returned false
+    pub(crate) fn finish(mut self) -> Option<Range<u64>> {
+        self.check();
+        self.found
+    }
+}
+
+// Finds the non-overlapping matches of an automaton in a text from left to right.
+// Each match starts as early as possible and is as long as possible from there.
+// An empty match right where the previous match ended is skipped, so "a*" finds
+// 0..0, 1..3 and 4..4 in "baac".
+pub struct Matches<'a> {
+    dfa: &'a DFA,
+    live_states: BTreeSet<u32>,
+    text: &'a str,
+    // The byte position where the search continues
+    position: usize,
+    last_end: Option<usize>
+}
+
+impl<'a> Matches<'a> {
+    pub(crate) fn new(dfa: &'a DFA, text: &'a str) -> Self {
+        Matches {
+            dfa,
+            live_states: dfa.live_states(),
+            text,
+            position: 0,
+            last_end: None
+        }
+    }
+
+    // Returns the next match from the position in one pass over the text
+    fn next_match(&self) -> Option<Range<usize>> {
+        let mut search = LeftmostLongest::new(self.dfa, &self.live_states, self.position as u64, self.last_end.map(|end| end as u64))?;
+
+        for ch in self.text[self.position..].chars() {
+            if !search.check() {
+                break;
+            }
+
+            search.feed_char(ch);
+        }
+
+        search.finish().map(|range| range.start as usize..range.end as usize)
+    }
+}
+
+impl<'a> Iterator for Matches<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        if self.position > self.text.len() {
+            return None;
+        }
+
+        match self.next_match() {
+            Some(range) => {
+                self.position = range.end;
+                self.last_end = Some(range.end);
+
+                Some(range)
+            },
+            None => {
+                // Nothing can start after the end of the text
+                self.position = self.text.len() + 1;
+                None
+            }
+        }
+    }
+}
+
+// Returns the parts of a text between the matches like str::split. Text that
+// starts or ends with a match gives an empty first or last part and with an empty
+// match, the parts can be empty too. After limit - 1 parts, the rest of the text
+// is the last part.
+pub struct Split<'a> {
+    matches: Matches<'a>,
+    text: &'a str,
+    // The byte position where the next part starts
+    start: usize,
+    limit: usize
+}
+
+impl<'a> Split<'a> {
+    pub(crate) fn new(dfa: &'a DFA, text: &'a str, limit: usize) -> Self {
+        Split {
+            matches: Matches::new(dfa, text),
+            text,
+            start: 0,
+            limit
+        }
+    }
+}
+
+impl<'a> Iterator for Split<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.limit == 0 {
+            return None;
+        }
+
+        self.limit -= 1;
+
+        match self.matches.next().filter(|_| self.limit > 0) {
+            Some(range) => {
+                let part = &self.text[self.start..range.start];
+                self.start = range.end;
+
+                Some(part)
+            },
+            None => {
+                self.limit = 0;
+                Some(&self.text[self.start..])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::Expression;
+
+    fn matches(pattern: &str, text: &str) -> Vec<Range<usize>> {
+        Matches::new(Expression::parse(pattern).unwrap().dfa(), text).collect()
+    }
+
+    #[test]
+    fn leftmost_longest() {
+        assert_eq!(matches("ab|abab", "xababab"), vec![1..5, 5..7]);
+        assert_eq!(matches("a|ab", "ab"), vec![0..2]);
+        assert_eq!(matches("b+", "abbcb"), vec![1..3, 4..5]);
+        assert_eq!(matches("c", "ab"), vec![]);
+    }
+
+    fn split(pattern: &str, text: &str, limit: usize) -> Vec<String> {
+        Split::new(Expression::parse(pattern).unwrap().dfa(), text, limit).map(|part| part.to_string()).collect()
+    }
+
+    #[test]
+    fn split_like_str() {
+        for text in &["a,b,,c", ",a,", "", ",", "abc"] {
+            assert_eq!(split(",", text, usize::MAX), text.split(',').collect::<Vec<_>>(), "{:?}", text);
+
+            for limit in 0..4 {
+                assert_eq!(split(",", text, limit), text.splitn(limit, ',').collect::<Vec<_>>(), "{:?} {}", text, limit);
+            }
+        }
+    }
+
+    #[test]
+    fn split_on_empty_matches() {
+        assert_eq!(split("x*", "abc", usize::MAX), "abc".split("").collect::<Vec<_>>());
+        assert_eq!(split("x*", "axxb", usize::MAX), vec!["", "a", "b", ""]);
+        assert_eq!(split("x*", "", usize::MAX), vec!["", ""]);
+    }
+
+    #[test]
+    fn empty_matches() {
+        assert_eq!(matches("a*", "baac"), vec![0..0, 1..3, 4..4]);
+        assert_eq!(matches("a*", ""), vec![0..0]);
+        assert_eq!(matches("a*", "é"), vec![0..0, 2..2]);
+    }
+
+    #[test]
+    fn earlier_start_wins_over_found_match() {
+        // "b" is found first, but the match of "abc" starts before it
+        assert_eq!(matches("abc|b", "abc"), vec![0..3]);
+        assert_eq!(matches("abc|b", "abd"), vec![1..2]);
+        assert_eq!(matches("a(b|c)*d|b", "xabcbd"), vec![1..6]);
+    }
+
+    #[test]
+    fn long_text_without_matches() {
+        // Trying every start on its own would read the text once per start
+        let text = "a".repeat(100_000);
+
+        assert_eq!(matches("a*b", &text), vec![]);
+        assert_eq!(matches("a*b", &(text.clone() + "b")), vec![0..100_001]);
+    }
+}
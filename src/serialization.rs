@@ -0,0 +1,196 @@
+use crate::nfa::NFA;
+use crate::dfa::DFA;
+use crate::transition::Transition;
+use crate::counter::Counter;
+
+use serde::Deserialize;
+
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use std::fmt;
+
+// Reasons for which deserialized data doesn't describe a valid automaton
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ValidationError {
+    MissingInitialState,
+    UnknownState(u32),
+    UnknownLabel(char),
+    NonDeterministicTransition(u32, char)
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::MissingInitialState                   => write!(f, "the automaton has no initial state"),
+            ValidationError::UnknownState(state)                   => write!(f, "state {} is not one of the automaton's states", state),
+            ValidationError::UnknownLabel(label)                   => write!(f, "label {:?} is not in the automaton's alphabet", label),
+            ValidationError::NonDeterministicTransition(from, label) => write!(f, "state {} has more than one transition with label {:?}", from, label)
+        }
+    }
+}
+
+fn check_states<'a>(states: &BTreeSet<u32>, used: impl IntoIterator<Item = &'a u32>) -> Result<(), ValidationError> {
+    match used.into_iter().find(|state| !states.contains(state)) {
+        Some(state) => Err(ValidationError::UnknownState(*state)),
+        None        => Ok(())
+    }
+}
+
+fn check_label(alphabet: &BTreeSet<char>, label: char) -> Result<(), ValidationError> {
+    if alphabet.contains(&label) {
+        Ok(())
+    }
+    else {
+        Err(ValidationError::UnknownLabel(label))
+    }
+}
+
+// The counter isn't serialized. The next state's id is simply the one after the largest id.
+fn counter_after(states: &BTreeSet<u32>) -> Counter {
+    let mut counter = Counter::new();
+    counter.value = states.iter().next_back().map_or(0, |s| s + 1);
+    counter
+}
+
+#[derive(Deserialize)]
+pub(crate) struct DFAData {
+    alphabet: BTreeSet<char>,
+    states: BTreeSet<u32>,
+    transitions: BTreeSet<Transition<char>>,
+    final_states: BTreeSet<u32>,
+    initial_state: Option<u32>
+}
+
+impl TryFrom<DFAData> for DFA {
+    type Error = ValidationError;
+
+    fn try_from(data: DFAData) -> Result<Self, ValidationError> {
+        let initial_state = data.initial_state.ok_or(ValidationError::MissingInitialState)?;
+
+        check_states(&data.states, &[initial_state])?;
+        check_states(&data.states, &data.final_states)?;
+
+        let mut previous: Option<&Transition<char>> = None;
+
+        // The transitions are sorted by their start and label, so two transitions
+        // with the same start and label are always next to each other
+        for transition in &data.transitions {
+            check_states(&data.states, &[transition.from, transition.to])?;
+            check_label(&data.alphabet, transition.label)?;
+
+            if let Some(previous) = previous {
+                if previous.from == transition.from && previous.label == transition.label {
+                    return Err(ValidationError::NonDeterministicTransition(transition.from, transition.label));
+                }
+            }
+
+            previous = Some(transition);
+        }
+
+        let mut dfa = DFA::new();
+
+        dfa.counter = counter_after(&data.states);
+        dfa.alphabet = data.alphabet;
+        dfa.states = data.states;
+        dfa.transitions = data.transitions;
+        dfa.final_states = data.final_states;
+        dfa.initial_state = Some(initial_state);
+
+        Ok(dfa)
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct NFAData {
+    alphabet: BTreeSet<char>,
+    states: BTreeSet<u32>,
+    transitions: BTreeSet<Transition<Option<char>>>,
+    final_states: BTreeSet<u32>,
+    initial_states: BTreeSet<u32>
+}
+
+impl TryFrom<NFAData> for NFA {
+    type Error = ValidationError;
+
+    fn try_from(data: NFAData) -> Result<Self, ValidationError> {
+        check_states(&data.states, &data.initial_states)?;
+        check_states(&data.states, &data.final_states)?;
+
+        for transition in &data.transitions {
+            check_states(&data.states, &[transition.from, transition.to])?;
+
+            if let Some(label) = transition.label {
+                check_label(&data.alphabet, label)?;
+            }
+        }
+
+        let mut nfa = NFA::new();
+
+        nfa.counter = counter_after(&data.states);
+        nfa.alphabet = data.alphabet;
+        nfa.states = data.states;
+        nfa.transitions = data.transitions;
+        nfa.final_states = data.final_states;
+        nfa.initial_states = data.initial_states;
+
+        Ok(nfa)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_dfa(json: &str) -> Result<DFA, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn dfa_round_trip() {
+        let dfa = DFA::from_nfa(NFA::from_string("ab*|c").unwrap()).minimize();
+        let json = serde_json::to_string(&dfa).unwrap();
+        let loaded: DFA = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.alphabet, dfa.alphabet);
+        assert_eq!(loaded.states, dfa.states);
+        assert_eq!(loaded.transitions, dfa.transitions);
+        assert_eq!(loaded.final_states, dfa.final_states);
+        assert_eq!(loaded.initial_state, dfa.initial_state);
+        assert_eq!(loaded.counter.value, dfa.counter.value);
+    }
+
+    #[test]
+    fn nfa_round_trip() {
+        let nfa = NFA::from_string("ab?|c*").unwrap();
+        let json = serde_json::to_string(&nfa).unwrap();
+        let loaded: NFA = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.alphabet, nfa.alphabet);
+        assert_eq!(loaded.states, nfa.states);
+        assert_eq!(loaded.transitions, nfa.transitions);
+        assert_eq!(loaded.final_states, nfa.final_states);
+        assert_eq!(loaded.initial_states, nfa.initial_states);
+        assert_eq!(loaded.counter.value, nfa.counter.value);
+    }
+
+    #[test]
+    fn dfa_validation() {
+        let valid = r#"{"alphabet":["a"],"states":[0,1],"transitions":[{"from":0,"label":"a","to":1}],"final_states":[1],"initial_state":0}"#;
+        assert!(parse_dfa(valid).is_ok());
+
+        let missing_initial = r#"{"alphabet":["a"],"states":[0],"transitions":[],"final_states":[],"initial_state":null}"#;
+        assert!(parse_dfa(missing_initial).unwrap_err().contains("no initial state"));
+
+        let unknown_initial = r#"{"alphabet":["a"],"states":[0],"transitions":[],"final_states":[],"initial_state":3}"#;
+        assert!(parse_dfa(unknown_initial).unwrap_err().contains("state 3"));
+
+        let unknown_target = r#"{"alphabet":["a"],"states":[0],"transitions":[{"from":0,"label":"a","to":5}],"final_states":[],"initial_state":0}"#;
+        assert!(parse_dfa(unknown_target).unwrap_err().contains("state 5"));
+
+        let unknown_label = r#"{"alphabet":["a"],"states":[0],"transitions":[{"from":0,"label":"b","to":0}],"final_states":[],"initial_state":0}"#;
+        assert!(parse_dfa(unknown_label).unwrap_err().contains("label 'b'"));
+
+        let non_deterministic = r#"{"alphabet":["a"],"states":[0,1],"transitions":[{"from":0,"label":"a","to":0},{"from":0,"label":"a","to":1}],"final_states":[],"initial_state":0}"#;
+        assert!(parse_dfa(non_deterministic).unwrap_err().contains("more than one transition"));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::dfa::DFA;
+
+use std::collections::BTreeSet;
+
+// Iterates over the strings accepted by a DFA in shortlex order, meaning that
+// shorter strings come first and strings of the same length are ordered
+// lexicographically.
+pub(crate) struct Strings<'a> {
+    dfa: &'a DFA,
+
+    // States from which a final state can be reached. Prefixes that end up
+    // in any other state (like the dead state) can never be completed, so
+    // they are dropped as soon as they are found.
+    live_states: BTreeSet<u32>,
+
+    // All completable prefixes of the current length together with the state
+    // they lead to. They are kept in lexicographic order.
+    frontier: Vec<(String, u32)>,
+    position: usize,
+    length: usize,
+    max_length: Option<usize>
+}
+
+impl<'a> Strings<'a> {
+    pub(crate) fn new(dfa: &'a DFA, max_length: Option<usize>) -> Self {
+        let live_states = dfa.live_states();
+        let mut frontier = Vec::new();
+
+        if let Some(initial_state) = dfa.initial_state {
+            if live_states.contains(&initial_state) {
+                frontier.push((String::new(), initial_state));
+            }
+        }
+
+        Strings {
+            dfa,
+            live_states,
+            frontier,
+            position: 0,
+            length: 0,
+            max_length
+        }
+    }
+
+    // Replaces the frontier with all completable prefixes that are one
+    // character longer. Since the transitions of a state are sorted by their
+    // label, extending the prefixes in order keeps the new frontier sorted.
+    fn advance(&mut self) {
+        let mut next_frontier = Vec::new();
+
+        for (prefix, state) in &self.frontier {
+            for transition in self.dfa.transitions_from(*state) {
+                if self.live_states.contains(&transition.to) {
+                    let mut next_prefix = prefix.clone();
+                    next_prefix.push(transition.label);
+                    next_frontier.push((next_prefix, transition.to));
+                }
+            }
+        }
+
+        self.frontier = next_frontier;
+        self.position = 0;
+        self.length += 1;
+    }
+}
+
+impl<'a> Iterator for Strings<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            while self.position < self.frontier.len() {
+                let (prefix, state) = &self.frontier[self.position];
+                self.position += 1;
+
+                if self.dfa.final_states.contains(state) {
+                    return Some(prefix.clone());
+                }
+            }
+
+            if self.frontier.is_empty() {
+                return None;
+            }
+
+            if let Some(max_length) = self.max_length {
+                if self.length >= max_length {
+                    return None;
+                }
+            }
+
+            self.advance();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transition::Transition;
+
+    #[test]
+    fn strings_of_finite_language() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0, 1, 2, 3];
+        dfa.counter.value = 4;
+
+        dfa.initial_state = Some(1);
+        dfa.final_states = set![2, 3];
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 0),
+            Transition::new(0, 'b', 0),
+            Transition::new(1, 'a', 3),
+            Transition::new(1, 'b', 2),
+            Transition::new(2, 'a', 0),
+            Transition::new(2, 'b', 3),
+            Transition::new(3, 'a', 0),
+            Transition::new(3, 'b', 0)
+        ];
+
+        let strings: Vec<String> = Strings::new(&dfa, None).collect();
+        assert_eq!(strings, vec!["a", "b", "bb"]);
+    }
+
+    #[test]
+    fn strings_of_infinite_language_up_to_length() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0];
+        dfa.counter.value = 1;
+
+        dfa.initial_state = Some(0);
+        dfa.final_states = set![0];
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 0),
+            Transition::new(0, 'b', 0)
+        ];
+
+        let strings: Vec<String> = Strings::new(&dfa, Some(2)).collect();
+        assert_eq!(strings, vec!["", "a", "b", "aa", "ab", "ba", "bb"]);
+    }
+
+    #[test]
+    fn strings_of_empty_language() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a'];
+        dfa.states = set![0];
+        dfa.counter.value = 1;
+
+        dfa.initial_state = Some(0);
+        dfa.transitions = set![Transition::new(0, 'a', 0)];
+
+        assert_eq!(Strings::new(&dfa, None).next(), None);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::dfa::DFA;
+
+use std::collections::BTreeMap;
+
+// Matches the text against a dense transition table. The table has a row for each
+// state and a column for each char of the sorted alphabet.
+fn run_table(alphabet: &[char], final_states: &[bool], transitions: &[Option<u32>], initial_state: Option<u32>, text: &str) -> bool {
+    let mut current_state = match initial_state {
+        Some(state) => state,
+        None        => return false
+    };
+
+    for ch in text.chars() {
+        let column = match alphabet.binary_search(&ch) {
+            Ok(column) => column,
+            Err(_)     => return false
+        };
+
+        match transitions[current_state as usize * alphabet.len() + column] {
+            Some(state) => current_state = state,
+            None        => return false
+        }
+    }
+
+    final_states[current_state as usize]
+}
+
+// A DFA stored as a dense transition table. The states are numbered from 0 in
+// the order of the original DFA's states.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DfaTable {
+    // Sorted, so that a char's column can be found with a binary search
+    pub alphabet: Vec<char>,
+    pub final_states: Vec<bool>,
+
+    // A row for each state and a column for each char of the alphabet.
+    // The transition of state s with the alphabet's i-th char is at s * alphabet.len() + i.
+    pub transitions: Vec<Option<u32>>,
+    pub initial_state: Option<u32>
+}
+
+impl DfaTable {
+    pub(crate) fn from_dfa(dfa: &DFA) -> Self {
+        let state_ids: BTreeMap<u32, u32> = dfa.states.iter().enumerate().map(|(i, s)| (*s, i as u32)).collect();
+        let alphabet: Vec<char> = dfa.alphabet.iter().cloned().collect();
+        let mut transitions = vec![None; dfa.states.len() * alphabet.len()];
+
+        for transition in &dfa.transitions {
+            let column = alphabet.binary_search(&transition.label).expect("Transition label is not in the alphabet");
+            transitions[state_ids[&transition.from] as usize * alphabet.len() + column] = Some(state_ids[&transition.to]);
+        }
+
+        DfaTable {
+            final_states: dfa.states.iter().map(|s| dfa.final_states.contains(s)).collect(),
+            initial_state: dfa.initial_state.and_then(|s| state_ids.get(&s).cloned()),
+            alphabet,
+            transitions
+        }
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.final_states.len()
+    }
+
+    // Returns the state that the transition with the letter leads to or None if
+    // there is no such transition or state
+    pub fn next_state(&self, state: u32, letter: char) -> Option<u32> {
+        let column = self.alphabet.binary_search(&letter).ok()?;
+        self.transitions.get(state as usize * self.alphabet.len() + column).cloned().flatten()
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        run_table(&self.alphabet, &self.final_states, &self.transitions, self.initial_state, text)
+    }
+}
+
+// A dense transition table that lives in static memory. It is built by the
+// automaton! macro from the automata-macros crate, so that patterns are compiled
+// at compile time.
+#[derive(Clone, Copy, Debug)]
+pub struct StaticDfa {
+    alphabet: &'static [char],
+    final_states: &'static [bool],
+    transitions: &'static [Option<u32>],
+    initial_state: Option<u32>
+}
+
+impl StaticDfa {
+    // Used by the generated code. The parts have the same layout as in DfaTable
+    // and are not validated.
+    #[doc(hidden)]
+    pub const fn from_static_parts(alphabet: &'static [char], final_states: &'static [bool], transitions: &'static [Option<u32>], initial_state: Option<u32>) -> Self {
+        StaticDfa {
+            alphabet,
+            final_states,
+            transitions,
+            initial_state
+        }
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.final_states.len()
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        run_table(self.alphabet, self.final_states, self.transitions, self.initial_state, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transition::Transition;
+
+    #[test]
+    fn from_dfa() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0, 2, 5];
+        dfa.counter.value = 6;
+
+        dfa.initial_state = Some(2);
+        dfa.final_states = set![5];
+
+        dfa.transitions = set![
+            Transition::new(2, 'a', 5),
+            Transition::new(5, 'b', 0),
+            Transition::new(5, 'a', 5)
+        ];
+
+        let table = DfaTable::from_dfa(&dfa);
+
+        assert_eq!(table.alphabet, vec!['a', 'b']);
+        assert_eq!(table.final_states, vec![false, false, true]);
+        assert_eq!(table.initial_state, Some(1));
+        assert_eq!(table.transitions, vec![None, None, Some(2), None, Some(2), Some(0)]);
+
+        assert!(table.matches("a"));
+        assert!(table.matches("aaa"));
+        assert!(!table.matches("ab"));
+        assert!(!table.matches("c"));
+    }
+
+    #[test]
+    fn next_state_out_of_range() {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a'];
+        dfa.states = set![0, 1];
+        dfa.counter.value = 2;
+        dfa.initial_state = Some(0);
+        dfa.transitions = set![Transition::new(0, 'a', 1)];
+
+        let table = DfaTable::from_dfa(&dfa);
+
+        assert_eq!(table.next_state(0, 'a'), Some(1));
+        assert_eq!(table.next_state(1, 'a'), None);
+        assert_eq!(table.next_state(2, 'a'), None);
+        assert_eq!(table.next_state(u32::MAX, 'a'), None);
+    }
+
+    #[test]
+    fn static_dfa() {
+        static DFA: StaticDfa = StaticDfa::from_static_parts(&['a', 'b'], &[false, true], &[Some(1), None, Some(1), Some(1)], Some(0));
+
+        assert_eq!(DFA.state_count(), 2);
+        assert!(DFA.matches("a"));
+        assert!(DFA.matches("abba"));
+        assert!(!DFA.matches(""));
+        assert!(!DFA.matches("b"));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::dfa::DFA;
+
+use std::error::Error;
+use std::fmt;
+
+// A single move of the automaton from one state to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub before: u32,
+    pub symbol: char,
+    pub after: u32
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    // The symbol is not in the alphabet, so the state has no transition for it
+    MissingTransition(char),
+    // The symbol leads to a state from which no final state can be reached
+    DeadState(char),
+    // The whole text was read, but the last state is not final
+    NonFinalState
+}
+
+// Why a text was rejected. The position is the index of the char that caused
+// the failure or the length of the text in chars if it ended too early.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceFailure {
+    pub kind: FailureKind,
+    pub position: usize
+}
+
+impl fmt::Display for TraceFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            FailureKind::MissingTransition(symbol) => write!(f, "{:?} at position {} is not in the alphabet", symbol, self.position),
+            FailureKind::DeadState(symbol)         => write!(f, "{:?} at position {} can't continue a match", symbol, self.position),
+            FailureKind::NonFinalState             => write!(f, "the text ends at position {} before a match is complete", self.position)
+        }
+    }
+}
+
+impl Error for TraceFailure {}
+
+// The steps that the automaton took on a text. The steps stop at the first failure,
+// because nothing after it can change the result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    pub initial_state: Option<u32>,
+    pub steps: Vec<Step>,
+    pub failure: Option<TraceFailure>,
+    // The longest prefix of the text that is matched
+    pub longest_match: Option<String>
+}
+
+impl Trace {
+    pub(crate) fn new(dfa: &DFA, text: &str) -> Self {
+        let mut trace = Trace {
+            initial_state: dfa.initial_state,
+            steps: vec![],
+            failure: None,
+            longest_match: None
+        };
+
+        let mut state = match dfa.initial_state {
+            Some(state) => state,
+            None        => {
+                trace.failure = Some(TraceFailure { kind: FailureKind::NonFinalState, position: 0 });
+                return trace;
+            }
+        };
+
+        let live_states = dfa.live_states();
+        let mut read = 0;
+
+        for (position, symbol) in text.chars().enumerate() {
+            if dfa.final_states.contains(&state) {
+                trace.longest_match = Some(text[..read].to_string());
+            }
+
+            let after = match dfa.reachable(state, symbol) {
+                Some(after) => after,
+                None        => {
+                    trace.failure = Some(TraceFailure { kind: FailureKind::MissingTransition(symbol), position });
+                    return trace;
+                }
+            };
+
+            trace.steps.push(Step { before: state, symbol, after });
+
+            if !live_states.contains(&after) {
+                trace.failure = Some(TraceFailure { kind: FailureKind::DeadState(symbol), position });
+                return trace;
+            }
+
+            state = after;
+            read += symbol.len_utf8();
+        }
+
+        if dfa.final_states.contains(&state) {
+            trace.longest_match = Some(text.to_string());
+        }
+        else {
+            trace.failure = Some(TraceFailure { kind: FailureKind::NonFinalState, position: text.chars().count() });
+        }
+
+        trace
+    }
+
+    pub fn is_match(&self) -> bool {
+        self.failure.is_none()
+    }
+
+    // Returns the state the automaton stopped in
+    pub fn last_state(&self) -> Option<u32> {
+        self.steps.last().map(|step| step.after).or(self.initial_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transition::Transition;
+
+    // Matches "ab" and "abb" over the alphabet {a, b}
+    fn dfa() -> DFA {
+        let mut dfa = DFA::new();
+
+        dfa.alphabet = set!['a', 'b'];
+        dfa.states = set![0, 1, 2, 3, 4];
+        dfa.counter.value = 5;
+
+        dfa.initial_state = Some(1);
+        dfa.final_states = set![3, 4];
+
+        dfa.transitions = set![
+            Transition::new(0, 'a', 0),
+            Transition::new(0, 'b', 0),
+            Transition::new(1, 'a', 2),
+            Transition::new(1, 'b', 0),
+            Transition::new(2, 'a', 0),
+            Transition::new(2, 'b', 3),
+            Transition::new(3, 'a', 0),
+            Transition::new(3, 'b', 4),
+            Transition::new(4, 'a', 0),
+            Transition::new(4, 'b', 0)
+        ];
+
+        dfa
+    }
+
+    #[test]
+    fn trace_match() {
+        let trace = Trace::new(&dfa(), "abb");
+
+        assert!(trace.is_match());
+        assert_eq!(trace.steps, vec![
+            Step { before: 1, symbol: 'a', after: 2 },
+            Step { before: 2, symbol: 'b', after: 3 },
+            Step { before: 3, symbol: 'b', after: 4 }
+        ]);
+        assert_eq!(trace.longest_match, Some("abb".to_string()));
+        assert_eq!(trace.last_state(), Some(4));
+    }
+
+    #[test]
+    fn trace_dead_state() {
+        let trace = Trace::new(&dfa(), "abbba");
+
+        assert_eq!(trace.steps.len(), 4);
+        assert_eq!(trace.failure, Some(TraceFailure { kind: FailureKind::DeadState('b'), position: 3 }));
+        assert_eq!(trace.longest_match, Some("abb".to_string()));
+        assert_eq!(trace.last_state(), Some(0));
+    }
+
+    #[test]
+    fn trace_missing_transition() {
+        let trace = Trace::new(&dfa(), "abc");
+
+        assert_eq!(trace.steps.len(), 2);
+        assert_eq!(trace.failure, Some(TraceFailure { kind: FailureKind::MissingTransition('c'), position: 2 }));
+        assert_eq!(trace.longest_match, Some("ab".to_string()));
+    }
+
+    #[test]
+    fn trace_non_final_state() {
+        let trace = Trace::new(&dfa(), "a");
+
+        assert_eq!(trace.failure, Some(TraceFailure { kind: FailureKind::NonFinalState, position: 1 }));
+        assert_eq!(trace.longest_match, None);
+        assert_eq!(trace.last_state(), Some(2));
+        assert_eq!(Trace::new(&dfa(), "").steps, vec![]);
+    }
+}
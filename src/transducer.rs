@@ -0,0 +1,490 @@
+use crate::transition::Transition;
+
+use std::collections::{BTreeSet, BTreeMap, VecDeque};
+
+// The states and transitions of a deterministic transducer. The states are numbered
+// from 0 in the order they are added and 0 is the initial state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StateGraph {
+    state_count: u32,
+    alphabet: BTreeSet<char>,
+    transitions: BTreeSet<Transition<char>>
+}
+
+impl StateGraph {
+    fn new() -> Self {
+        StateGraph {
+            state_count: 1,
+            alphabet: BTreeSet::new(),
+            transitions: BTreeSet::new()
+        }
+    }
+
+    fn add_state(&mut self) -> u32 {
+        self.state_count += 1;
+        self.state_count - 1
+    }
+
+    // Replaces the transition of the state with the input if there already is one
+    fn add_transition(&mut self, from: u32, input: char, to: u32) {
+        assert!(from < self.state_count && to < self.state_count, "Unknown state in transition {} -{}-> {}", from, input, to);
+
+        if let Some(old) = self.next_state(from, input) {
+            self.transitions.remove(&Transition::new(from, input, old));
+        }
+
+        self.alphabet.insert(input);
+        self.transitions.insert(Transition::new(from, input, to));
+    }
+
+    fn next_state(&self, state: u32, input: char) -> Option<u32> {
+        let start = Transition::new(state, input, 0);
+        let end = Transition::new(state, input, u32::MAX);
+
+        self.transitions.range(start..=end).next().map(|t| t.to)
+    }
+
+    fn transitions_from(&self, state: u32) -> impl Iterator<Item = &Transition<char>> {
+        let start = Transition::new(state, '\0', 0);
+        let end = Transition::new(state, char::MAX, u32::MAX);
+
+        self.transitions.range(start..=end)
+    }
+
+    // Returns the states that the initial state can reach in breadth first order
+    fn reachable_states(&self) -> Vec<u32> {
+        let mut res = vec![0];
+        let mut visited: BTreeSet<u32> = set![0];
+        let mut queue: VecDeque<u32> = vec![0].into();
+
+        while let Some(state) = queue.pop_front() {
+            for transition in self.transitions_from(state) {
+                if visited.insert(transition.to) {
+                    res.push(transition.to);
+                    queue.push_back(transition.to);
+                }
+            }
+        }
+
+        res
+    }
+
+    // Groups the reachable states that can't be told apart, like Minimizer does for DFAs.
+    // At first the states are grouped by their signature, which holds the outputs, and
+    // then the groups are split until all states of a group move into the same groups
+    // with each input. Returns the group of each state. The groups are numbered in the
+    // order they are reached from the initial state, so the initial state is in group 0.
+    fn equivalent_states<S: Ord, F: Fn(u32) -> S>(&self, signature: F) -> BTreeMap<u32, u32> {
+        let states = self.reachable_states();
+        let mut groups = BTreeMap::new();
+        let mut group_count = number_groups(&states, &mut groups, signature);
+
+        loop {
+            let previous = groups.clone();
+            let next_groups = |state: u32| -> (u32, Vec<Option<u32>>) {
+                let next = self.alphabet.iter().map(|input| self.next_state(state, *input).map(|next| previous[&next])).collect();
+                (previous[&state], next)
+            };
+
+            let new_group_count = number_groups(&states, &mut groups, next_groups);
+
+            // Splitting only ever adds groups, so nothing changed if there are as many as before
+            if new_group_count == group_count {
+                break;
+            }
+
+            group_count = new_group_count;
+        }
+
+        groups
+    }
+
+    // Builds the graph of the groups of states
+    fn merge(&self, groups: &BTreeMap<u32, u32>) -> StateGraph {
+        StateGraph {
+            state_count: groups.values().max().map_or(1, |max| max + 1),
+            alphabet: self.alphabet.clone(),
+            transitions: self.transitions
+                             .iter()
+                             .filter(|transition| groups.contains_key(&transition.from))
+                             .map(|transition| Transition::new(groups[&transition.from], transition.label, groups[&transition.to]))
+                             .collect()
+        }
+    }
+}
+
+// Numbers the states' keys in the order that the states come and returns how many
+// different keys there are
+fn number_groups<K: Ord, F: Fn(u32) -> K>(states: &[u32], groups: &mut BTreeMap<u32, u32>, key: F) -> u32 {
+    let mut ids = BTreeMap::new();
+
+    for state in states {
+        let next_id = ids.len() as u32;
+        let id = *ids.entry(key(*state)).or_insert(next_id);
+        groups.insert(*state, id);
+    }
+
+    ids.len() as u32
+}
+
+// A deterministic transducer that writes an output with each transition. Running it on
+// an input gives one output per input char.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MealyMachine<O> {
+    graph: StateGraph,
+    outputs: BTreeMap<(u32, char), O>
+}
+
+impl<O: Clone + Ord> MealyMachine<O> {
+    // Creates a machine with only the initial state 0
+    pub fn new() -> Self {
+        MealyMachine {
+            graph: StateGraph::new(),
+            outputs: BTreeMap::new()
+        }
+    }
+
+    pub fn add_state(&mut self) -> u32 {
+        self.graph.add_state()
+    }
+
+    // Panics if one of the states doesn't exist. Replaces the transition of the state
+    // with the input if there already is one.
+    pub fn add_transition(&mut self, from: u32, input: char, to: u32, output: O) {
+        self.graph.add_transition(from, input, to);
+        self.outputs.insert((from, input), output);
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.graph.state_count as usize
+    }
+
+    pub fn transition_count(&self) -> usize {
+        self.graph.transitions.len()
+    }
+
+    // Returns the next state and the output of the transition
+    pub fn step(&self, state: u32, input: char) -> Option<(u32, &O)> {
+        let next = self.graph.next_state(state, input)?;
+        Some((next, &self.outputs[&(state, input)]))
+    }
+
+    // Returns the outputs for the input or None if a state has no transition for a char
+    pub fn run(&self, input: &str) -> Option<Vec<O>> {
+        let mut state = 0;
+        let mut res = Vec::new();
+
+        for ch in input.chars() {
+            let (next, output) = self.step(state, ch)?;
+            res.push(output.clone());
+            state = next;
+        }
+
+        Some(res)
+    }
+
+    // Returns the machine with the fewest states that gives the same outputs for all inputs
+    pub fn minimize(self) -> Self {
+        let groups = self.graph.equivalent_states(|state| {
+            self.graph.alphabet.iter().map(|input| self.outputs.get(&(state, *input))).collect::<Vec<_>>()
+        });
+
+        MealyMachine {
+            graph: self.graph.merge(&groups),
+            outputs: self.outputs
+                         .iter()
+                         .filter(|((state, _), _)| groups.contains_key(state))
+                         .map(|((state, input), output)| ((groups[state], *input), output.clone()))
+                         .collect()
+        }
+    }
+}
+
+impl MealyMachine<char> {
+    // Returns a machine that feeds the outputs of this machine into the other machine.
+    // Its states are the pairs of states of both machines that the inputs can reach.
+    pub fn compose<P: Clone + Ord>(&self, other: &MealyMachine<P>) -> MealyMachine<P> {
+        let mut res = MealyMachine::new();
+        let mut pair_states = BTreeMap::new();
+        let mut queue = VecDeque::new();
+
+        pair_states.insert((0, 0), 0);
+        queue.push_back((0, 0));
+
+        while let Some((first, second)) = queue.pop_front() {
+            let state = pair_states[&(first, second)];
+
+            for transition in self.graph.transitions_from(first) {
+                let middle = self.outputs[&(first, transition.label)];
+
+                let (next_second, output) = match other.step(second, middle) {
+                    Some(step) => step,
+                    None       => continue
+                };
+
+                let next_pair = (transition.to, next_second);
+                let next = match pair_states.get(&next_pair) {
+                    Some(next) => *next,
+                    None => {
+                        let next = res.add_state();
+                        pair_states.insert(next_pair, next);
+                        queue.push_back(next_pair);
+                        next
+                    }
+                };
+
+                res.add_transition(state, transition.label, next, output.clone());
+            }
+        }
+
+        res
+    }
+}
+
+impl<O: Clone + Ord> Default for MealyMachine<O> {
+    fn default() -> Self {
+        MealyMachine::new()
+    }
+}
+
+// A deterministic transducer whose states have outputs. Running it on an input gives
+// the output of the initial state followed by the output of each state it moves into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MooreMachine<O> {
+    graph: StateGraph,
+    outputs: BTreeMap<u32, O>
+}
+
+impl<O: Clone + Ord> MooreMachine<O> {
+    // Creates a machine with only the initial state 0
+    pub fn new(initial_output: O) -> Self {
+        MooreMachine {
+            graph: StateGraph::new(),
+            outputs: vec![(0, initial_output)].into_iter().collect()
+        }
+    }
+
+    pub fn add_state(&mut self, output: O) -> u32 {
+        let state = self.graph.add_state();
+        self.outputs.insert(state, output);
+        state
+    }
+
+    // Panics if one of the states doesn't exist. Replaces the transition of the state
+    // with the input if there already is one.
+    pub fn add_transition(&mut self, from: u32, input: char, to: u32) {
+        self.graph.add_transition(from, input, to);
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.graph.state_count as usize
+    }
+
+    pub fn transition_count(&self) -> usize {
+        self.graph.transitions.len()
+    }
+
+    pub fn output(&self, state: u32) -> Option<&O> {
+        self.outputs.get(&state)
+    }
+
+    pub fn step(&self, state: u32, input: char) -> Option<u32> {
+        self.graph.next_state(state, input)
+    }
+
+    // Returns the outputs for the input, one more than there are chars, or None if
+    // a state has no transition for a char
+    pub fn run(&self, input: &str) -> Option<Vec<O>> {
+        let mut state = 0;
+        let mut res = vec![self.outputs[&0].clone()];
+
+        for ch in input.chars() {
+            state = self.step(state, ch)?;
+            res.push(self.outputs[&state].clone());
+        }
+
+        Some(res)
+    }
+
+    // Returns the machine with the fewest states that gives the same outputs for all inputs
+    pub fn minimize(self) -> Self {
+        let groups = self.graph.equivalent_states(|state| &self.outputs[&state]);
+
+        MooreMachine {
+            graph: self.graph.merge(&groups),
+            outputs: groups.iter().map(|(state, group)| (*group, self.outputs[state].clone())).collect()
+        }
+    }
+}
+
+impl MooreMachine<char> {
+    // Returns a machine that feeds the outputs of this machine into the other machine.
+    // The other machine reads the outputs of the states this machine moves into, so the
+    // output of the initial state is not read and the composition gives one output
+    // per input char like each of the machines.
+    pub fn compose<P: Clone + Ord>(&self, other: &MooreMachine<P>) -> MooreMachine<P> {
+        let mut res = MooreMachine::new(other.outputs[&0].clone());
+        let mut pair_states = BTreeMap::new();
+        let mut queue = VecDeque::new();
+
+        pair_states.insert((0, 0), 0);
+        queue.push_back((0, 0));
+
+        while let Some((first, second)) = queue.pop_front() {
+            let state = pair_states[&(first, second)];
+
+            for transition in self.graph.transitions_from(first) {
+                let next_second = match other.step(second, self.outputs[&transition.to]) {
+                    Some(next_second) => next_second,
+                    None              => continue
+                };
+
+                let next_pair = (transition.to, next_second);
+                let next = match pair_states.get(&next_pair) {
+                    Some(next) => *next,
+                    None => {
+                        let next = res.add_state(other.outputs[&next_second].clone());
+                        pair_states.insert(next_pair, next);
+                        queue.push_back(next_pair);
+                        next
+                    }
+                };
+
+                res.add_transition(state, transition.label, next);
+            }
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Non-return-to-zero inverted: a 1 flips the level and a 0 keeps it
+    fn nrzi_mealy() -> MealyMachine<char> {
+        let mut machine = MealyMachine::new();
+        let high = machine.add_state();
+
+        machine.add_transition(0, '0', 0, '0');
+        machine.add_transition(0, '1', high, '1');
+        machine.add_transition(high, '0', high, '1');
+        machine.add_transition(high, '1', 0, '0');
+        machine
+    }
+
+    fn nrzi_moore() -> MooreMachine<char> {
+        let mut machine = MooreMachine::new('0');
+        let high = machine.add_state('1');
+
+        machine.add_transition(0, '0', 0);
+        machine.add_transition(0, '1', high);
+        machine.add_transition(high, '0', high);
+        machine.add_transition(high, '1', 0);
+        machine
+    }
+
+    #[test]
+    fn run_mealy() {
+        let machine = nrzi_mealy();
+
+        assert_eq!(machine.run("1011"), Some(vec!['1', '1', '0', '1']));
+        assert_eq!(machine.run(""), Some(vec![]));
+        assert_eq!(machine.run("12"), None);
+    }
+
+    #[test]
+    fn run_moore() {
+        let machine = nrzi_moore();
+
+        assert_eq!(machine.run("1011"), Some(vec!['0', '1', '1', '0', '1']));
+        assert_eq!(machine.run(""), Some(vec!['0']));
+        assert_eq!(machine.run("12"), None);
+    }
+
+    #[test]
+    fn replaced_transitions() {
+        let mut machine = MealyMachine::new();
+
+        machine.add_transition(0, 'a', 0, 1);
+        machine.add_transition(0, 'a', 0, 2);
+
+        assert_eq!(machine.transition_count(), 1);
+        assert_eq!(machine.run("aa"), Some(vec![2, 2]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown state")]
+    fn unknown_state() {
+        MooreMachine::new(0).add_transition(0, 'a', 1);
+    }
+
+    #[test]
+    fn minimize_mealy() {
+        // Counts the chars modulo 2 with four states and an unreachable one
+        let mut machine = MealyMachine::new();
+        let states: Vec<u32> = (0..4).map(|_| machine.add_state()).collect();
+
+        machine.add_transition(0, 'a', states[0], 'o');
+        machine.add_transition(states[0], 'a', states[1], 'e');
+        machine.add_transition(states[1], 'a', states[2], 'o');
+        machine.add_transition(states[2], 'a', 0, 'e');
+        machine.add_transition(states[3], 'a', 0, 'x');
+
+        let minimized = machine.clone().minimize();
+
+        assert_eq!(minimized.state_count(), 2);
+        assert_eq!(minimized.transition_count(), 2);
+        assert_eq!(minimized.run("aaaaa"), machine.run("aaaaa"));
+    }
+
+    #[test]
+    fn minimize_moore() {
+        let mut machine = MooreMachine::new(0);
+        let a = machine.add_state(1);
+        let b = machine.add_state(1);
+
+        machine.add_transition(0, 'x', a);
+        machine.add_transition(0, 'y', b);
+        machine.add_transition(a, 'x', 0);
+        machine.add_transition(b, 'x', 0);
+
+        let minimized = machine.clone().minimize();
+
+        assert_eq!(minimized.state_count(), 2);
+        assert_eq!(minimized.run("xxyx"), machine.run("xxyx"));
+
+        // A missing transition tells states apart
+        machine.add_transition(b, 'y', b);
+        assert_eq!(machine.minimize().state_count(), 3);
+    }
+
+    #[test]
+    fn compose_mealy() {
+        let mut invert = MealyMachine::new();
+        invert.add_transition(0, '0', 0, '1');
+        invert.add_transition(0, '1', 0, '0');
+
+        let composed = invert.compose(&nrzi_mealy());
+
+        assert_eq!(composed.run("0100"), nrzi_mealy().run("1011"));
+        assert_eq!(composed.state_count(), 2);
+    }
+
+    #[test]
+    fn compose_moore() {
+        let mut invert = MooreMachine::new('0');
+        let zero = invert.add_state('1');
+        let one = invert.add_state('0');
+
+        for state in &[0, zero, one] {
+            invert.add_transition(*state, '0', zero);
+            invert.add_transition(*state, '1', one);
+        }
+
+        let composed = invert.compose(&nrzi_moore());
+
+        assert_eq!(composed.run("0100"), nrzi_moore().run("1011"));
+    }
+}
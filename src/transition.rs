@@ -1,6 +1,10 @@
 use std::cmp::Ordering;
 
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub(crate) struct Transition<T> {
     pub(crate) from: u32,
     pub(crate) label: T,
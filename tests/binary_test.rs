@@ -0,0 +1,22 @@
+#[cfg(test)]
+mod tests {
+    use automata::expression::Expression;
+    use automata::dfa::{DfaRef, FormatError};
+
+    #[test]
+    fn binary_dfa_matches_like_expression() {
+        let expression = Expression::parse("a(bc|d)*e?|\\*+").unwrap();
+        let bytes = expression.dfa().to_bytes();
+        let dfa = DfaRef::from_bytes(&bytes).unwrap();
+
+        for text in ["a", "abc", "adbcde", "***", "", "ab", "e", "a*"].iter() {
+            assert_eq!(dfa.matches(text), expression.matches(text));
+        }
+    }
+
+    #[test]
+    fn corrupt_data_is_rejected() {
+        assert_eq!(DfaRef::from_bytes(b"").unwrap_err(), FormatError::TooShort);
+        assert_eq!(DfaRef::from_bytes(b"not an automaton at all").unwrap_err(), FormatError::BadMagic);
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use std::io::{ErrorKind, Write};
+    use std::process::{Command, Output, Stdio};
+
+    fn run(args: &[&str], input: &str) -> Output {
+        let stdin = if input.is_empty() { Stdio::null() } else { Stdio::piped() };
+        let mut child = Command::new(env!("CARGO_BIN_EXE_automata"))
+                                .args(args)
+                                .stdin(stdin)
+                                .stdout(Stdio::piped())
+                                .stderr(Stdio::piped())
+                                .spawn()
+                                .unwrap();
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // The command can exit before reading its input, for example on invalid arguments
+            if let Err(error) = stdin.write_all(input.as_bytes()) {
+                assert_eq!(error.kind(), ErrorKind::BrokenPipe, "Can't write the input: {}", error);
+            }
+        }
+
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn grep_stdin() {
+        let output = run(&["grep", "-n", "Ivan|Petq"], "Ivan Ivanov\nPetar\nPetq\n");
+
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "1:Ivan Ivanov\n3:Petq\n");
+    }
+
+    #[test]
+    fn grep_files() {
+        let dir = std::env::temp_dir().join(format!("automata_cli_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let first = dir.join("first.txt");
+        let second = dir.join("second.txt");
+        std::fs::write(&first, "ab\nabc\n").unwrap();
+        std::fs::write(&second, "abbc\nc\n").unwrap();
+
+        let output = run(&["grep", "-xc", "ab+c", first.to_str().unwrap(), second.to_str().unwrap()], "");
+        let expected = format!("{}:1\n{}:1\n", first.display(), second.display());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+    }
+
+    #[test]
+    fn grep_exit_codes() {
+        assert_eq!(run(&["grep", "x"], "abc\n").status.code(), Some(1));
+        assert_eq!(run(&["grep", "a(b"], "abc\n").status.code(), Some(2));
+        assert_eq!(run(&["grep", "a", "/nonexistent/file"], "").status.code(), Some(2));
+        assert_eq!(run(&["unknown"], "").status.code(), Some(2));
+
+        // More input than a pipe holds, which the command never reads
+        assert_eq!(run(&["grep", "a(b"], &"abc\n".repeat(100_000)).status.code(), Some(2));
+    }
+
+    #[test]
+    fn inspect_stages() {
+        let output = run(&["min", "ab"], "");
+
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), concat!(
+            "Minimized DFA: 4 states, 8 transitions\n",
+            "\n",
+            "     a b\n",
+            "   0 0 0\n",
+            "-> 1 2 0\n",
+            "   2 0 3\n",
+            " * 3 0 0\n"
+        ));
+
+        let output = run(&["nfa", "--dot", "a|b"], "");
+
+        assert_eq!(output.status.code(), Some(0));
+        assert!(String::from_utf8(output.stdout).unwrap().starts_with("digraph"));
+        assert_eq!(run(&["dfa", "a)"], "").status.code(), Some(2));
+        assert_eq!(run(&["nfa", "--hide-dead", "a"], "").status.code(), Some(2));
+    }
+
+    #[test]
+    fn repl_session() {
+        let output = run(&["repl", "ab*"], "abb\n:equiv a|ab\n:quit\nab\n");
+
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), concat!(
+            "accepted: 1 -a-> 2 -b-> 2 -b-> 2\n",
+            "not equivalent: \"abb\" is matched by only the current pattern\n"
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+mod generated {
+    include!("generated/rust_matcher.rs");
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::expression::Expression;
+    use super::generated::generated_matches;
+
+    use std::fmt::Write;
+    use std::process::Command;
+
+    // The generated file has to be updated whenever this pattern or the code generation changes
+    const PATTERN: &str = "a+bc*|c(a|\\*)*|(x|y|z)+é";
+
+    #[test]
+    fn generated_rust_source_is_up_to_date() {
+        let source = Expression::parse(PATTERN).unwrap().dfa().to_rust_source("generated_matches");
+        assert_eq!(source, include_str!("generated/rust_matcher.rs"));
+    }
+
+    #[test]
+    fn generated_rust_source_agrees_with_expression() {
+        let expression = Expression::parse(PATTERN).unwrap();
+
+        for text in expression.strings_up_to(5) {
+            assert!(generated_matches(&text));
+        }
+
+        for text in ["", "a", "b", "bc", "abcb", "ca*b", "xé", "é", "xyzzy", "abd"].iter() {
+            assert_eq!(generated_matches(text), expression.matches(text));
+        }
+    }
+
+    // Builds a C program that checks the generated function on each text. The texts are
+    // copied into heap buffers of their exact size, so that reading past the nul is
+    // caught when the program is built with the address sanitizer.
+    fn c_test_program(source: &str, cases: &[(&[u8], bool)]) -> String {
+        let mut res = String::from(source);
+
+        res.push_str("\n#include <stdio.h>\n#include <stdlib.h>\n#include <string.h>\n\n");
+        res.push_str("static int check(const char *bytes, size_t length, bool expected, int index) {\n");
+        res.push_str("    char *text = malloc(length + 1);\n");
+        res.push_str("    memcpy(text, bytes, length + 1);\n");
+        res.push_str("    bool matched = is_match(text);\n");
+        res.push_str("    free(text);\n\n");
+        res.push_str("    if (matched != expected) {\n");
+        res.push_str("        printf(\"case %d: expected %d\\n\", index, expected);\n");
+        res.push_str("        return 1;\n");
+        res.push_str("    }\n\n");
+        res.push_str("    return 0;\n");
+        res.push_str("}\n\nint main(void) {\n    int failures = 0;\n");
+
+        for (index, (bytes, expected)) in cases.iter().enumerate() {
+            let literal: String = bytes.iter().map(|byte| format!("\"\\x{:02X}\" ", byte)).collect();
+            let _ = writeln!(res, "    failures += check({}\"\", {}, {}, {});", literal, bytes.len(), expected, index);
+        }
+
+        res.push_str("    return failures;\n}\n");
+        res
+    }
+
+    #[test]
+    fn generated_c_source_rejects_invalid_utf8() {
+        let expression = Expression::new("a+é|€|𝄞x");
+        let directory = std::env::temp_dir().join(format!("automata_c_test_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let cases: Vec<(&[u8], bool)> = vec![
+            ("aé".as_bytes(), true),
+            (b"a", false),
+            ("aaé".as_bytes(), true),
+            ("€".as_bytes(), true),
+            ("𝄞x".as_bytes(), true),
+            (b"", false),
+            // Cut short by the nul
+            (b"\xE0", false),
+            (b"a\xC3", false),
+            (b"\xE2\x82", false),
+            (b"\xF0\x9D\x84", false),
+            // Not followed by a continuation byte
+            (b"a\xC3a", false),
+            (b"\xE2\x82a", false),
+            // Overlong encodings, surrogates, too large and stray continuation bytes
+            (b"\xC0\xE1", false),
+            (b"\xE0\x82\xAC", false),
+            (b"\xED\xA0\x80", false),
+            (b"\xF4\x90\x80\x80", false),
+            (b"\xA9", false)
+        ];
+
+        let program = c_test_program(&expression.dfa().to_c_source("is_match"), &cases);
+        let source_path = directory.join("test.c");
+        let binary_path = directory.join("test");
+        std::fs::write(&source_path, program).unwrap();
+
+        let compile = |extra_flags: &[&str]| {
+            Command::new("cc").args(extra_flags).arg("-std=c99").arg("-Wall").arg("-Werror").arg(&source_path).arg("-o").arg(&binary_path).status()
+        };
+
+        // Prefer the address sanitizer, but not every compiler has it
+        let compiled = match compile(&["-fsanitize=address"]) {
+            Ok(status) if status.success() => true,
+            Ok(_) => compile(&[]).map(|status| status.success()).unwrap_or(false),
+            Err(_) => {
+                eprintln!("Skipping the C test, because there is no C compiler");
+                return;
+            }
+        };
+
+        assert!(compiled, "The generated C source doesn't compile");
+
+        let output = Command::new(&binary_path).output().unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert!(output.status.success(), "{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    }
+}
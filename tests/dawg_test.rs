@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use automata::dawg::Dawg;
+    use automata::levenshtein::LevenshteinAutomaton;
+
+    fn keywords() -> Vec<String> {
+        let mut words: Vec<String> = (0..3000).map(|index| format!("key{}", index * 7)).collect();
+        words.extend(vec!["break".to_string(), "continue".to_string(), "const".to_string(), "crate".to_string()]);
+        words.sort();
+        words
+    }
+
+    #[test]
+    fn membership() {
+        let words = keywords();
+        let dawg = Dawg::from_sorted(&words).unwrap();
+
+        assert_eq!(dawg.len(), words.len());
+
+        for word in &words {
+            assert!(dawg.contains(word), "{}", word);
+        }
+
+        assert!(!dawg.contains("key1"));
+        assert!(!dawg.contains("key"));
+        assert!(!dawg.contains("crates"));
+    }
+
+    #[test]
+    fn iteration_in_sorted_order() {
+        let words = keywords();
+        let dawg = Dawg::from_sorted(&words).unwrap();
+
+        assert_eq!(dawg.words().collect::<Vec<_>>(), words);
+    }
+
+    #[test]
+    fn prefix_queries() {
+        let dawg = Dawg::from_sorted(keywords()).unwrap();
+
+        assert!(dawg.has_prefix("con"));
+        assert!(dawg.has_prefix(""));
+        assert!(!dawg.has_prefix("cons t"));
+        assert_eq!(dawg.words_with_prefix("con").collect::<Vec<_>>(), vec!["const", "continue"]);
+        assert_eq!(dawg.words_with_prefix("key700").collect::<Vec<_>>(), vec!["key700", "key7000", "key7007"]);
+        assert_eq!(dawg.words_with_prefix("x").count(), 0);
+    }
+
+    #[test]
+    fn small_automaton() {
+        // All numbers from 0 to 99999 share their states
+        let words: Vec<String> = (0..100_000).map(|number| format!("{:05}", number)).collect();
+        let dawg = Dawg::from_sorted(&words).unwrap();
+
+        assert_eq!(dawg.len(), 100_000);
+        assert_eq!(dawg.dfa().state_count(), 6);
+        assert!(dawg.contains("04217"));
+    }
+
+    #[test]
+    fn unsorted_words() {
+        let error = Dawg::from_sorted(["b", "c", "a"]).unwrap_err();
+
+        assert_eq!(error.previous, "c");
+        assert_eq!(error.word, "a");
+    }
+
+    #[test]
+    fn fuzzy_search_in_dawg() {
+        let expression = Dawg::from_sorted(keywords()).unwrap().into_expression();
+        let suggestions: Vec<String> = LevenshteinAutomaton::new("cnst", 1).intersect(&expression).strings().collect();
+
+        assert!(expression.matches("crate"));
+        assert_eq!(suggestions, vec!["const"]);
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use automata::expression::Expression;
+    use automata::expression::{Expression, FailureKind};
 
     #[test]
     fn match_plain_text() {
@@ -104,4 +104,124 @@ mod tests {
         assert!(!expression.matches("b"));
         assert!(!expression.matches("bc"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn match_text_with_groups() {
+        let expression = Expression::parse("a(bc|d)*e?").unwrap();
+
+        assert!(expression.matches("a"));
+        assert!(expression.matches("ae"));
+        assert!(expression.matches("abc"));
+        assert!(expression.matches("adbcde"));
+
+        assert!(!expression.matches("ab"));
+        assert!(!expression.matches("abcb"));
+        assert!(!expression.matches("aee"));
+        assert!(!expression.matches("e"));
+    }
+
+    #[test]
+    fn match_text_with_escaped_chars() {
+        let expression = Expression::parse(r"a\\*\(\|\)").unwrap();
+
+        assert!(expression.matches("a(|)"));
+        assert!(expression.matches(r"a\\(|)"));
+
+        assert!(!expression.matches("a"));
+        assert!(!expression.matches("a()"));
+    }
+
+    #[test]
+    fn parse_invalid_expressions() {
+        use automata::expression::{ParseError, ParseErrorKind};
+
+        assert!(Expression::parse("a(b|c)").is_ok());
+        assert_eq!(Expression::parse("a(b|c").err(), Some(ParseError { kind: ParseErrorKind::UnclosedGroup, position: 1 }));
+        assert_eq!(Expression::parse("+a").err(), Some(ParseError { kind: ParseErrorKind::MissingOperand('+'), position: 0 }));
+
+        // Parentheses that are matched literally have to be escaped
+        assert!(Expression::parse("(").is_err());
+        assert!(Expression::parse(r"\(").unwrap().matches("("));
+    }
+
+    #[test]
+    fn new_keeps_the_basic_syntax() {
+        // Every string is valid and parentheses and '\' are matched literally
+        assert!(Expression::new("(").matches("("));
+        assert!(Expression::new("a)").matches("a)"));
+        assert!(Expression::new(r"a\b").matches(r"a\b"));
+        assert!(Expression::new("()").matches("()"));
+        assert!(!Expression::new("()").matches(""));
+
+        let expression = Expression::new("(a|b)");
+
+        assert!(expression.matches("(a"));
+        assert!(expression.matches("b)"));
+        assert!(!expression.matches("a"));
+
+        // An operator that doesn't follow a char is a char itself
+        let expression = Expression::new("*a|b**");
+
+        assert!(expression.matches("*a"));
+        assert!(expression.matches("bb*"));
+        assert!(expression.matches("*"));
+        assert!(!expression.matches("a"));
+    }
+
+    #[test]
+    fn parse_deeply_nested_groups() {
+        use automata::expression::{ParseError, ParseErrorKind, MAX_NESTING};
+
+        let nested = format!("{}a{}", "(".repeat(10_000), ")".repeat(10_000));
+        assert_eq!(Expression::parse(&nested).err(), Some(ParseError { kind: ParseErrorKind::NestingTooDeep, position: MAX_NESTING }));
+
+        let nested = format!("{}a{}", "(".repeat(MAX_NESTING), ")".repeat(MAX_NESTING));
+        assert!(Expression::parse(&nested).unwrap().matches("a"));
+    }
+
+    #[test]
+    fn equivalent_expressions() {
+        let expression = Expression::parse("(a|b)*").unwrap();
+
+        assert!(expression.is_equivalent(&Expression::parse("(a*b*)*").unwrap()));
+        assert!(!expression.is_equivalent(&Expression::parse("(ab)*").unwrap()));
+        assert_eq!(expression.distinguishing_string(&Expression::parse("(ab)*").unwrap()), Some("a".to_string()));
+        assert_eq!(expression.distinguishing_string(&Expression::new("a*|b*")), Some("ab".to_string()));
+    }
+
+    #[test]
+    fn trace_rejected_strings() {
+        let expression = Expression::new("a+bc*|ca*");
+
+        let trace = expression.trace("aabcx");
+        assert_eq!(trace.failure.unwrap().kind, FailureKind::MissingTransition('x'));
+        assert_eq!(trace.failure.unwrap().position, 4);
+        assert_eq!(trace.longest_match, Some("aabc".to_string()));
+        assert_eq!(trace.steps.len(), 4);
+
+        let trace = expression.trace("cab");
+        assert_eq!(trace.failure.unwrap().kind, FailureKind::DeadState('b'));
+        assert_eq!(trace.longest_match, Some("ca".to_string()));
+        assert_eq!(trace.failure.unwrap().to_string(), "'b' at position 2 can't continue a match");
+
+        let trace = expression.trace("aa");
+        assert_eq!(trace.failure.unwrap().kind, FailureKind::NonFinalState);
+        assert_eq!(trace.longest_match, None);
+        assert_eq!(trace.failure.unwrap().to_string(), "the text ends at position 2 before a match is complete");
+    }
+
+    #[test]
+    fn trace_follows_matches() {
+        let expression = Expression::parse("(a|b)*c|ba+").unwrap();
+
+        for text in &["", "c", "abc", "baa", "bac", "ca", "abab"] {
+            let trace = expression.trace(text);
+
+            assert_eq!(trace.is_match(), expression.matches(text));
+
+            for pair in trace.steps.windows(2) {
+                assert_eq!(pair[0].after, pair[1].before);
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use automata::expression::Expression;
+    use automata::nfa::NFA;
+    use automata::dfa::DFA;
+
+    #[test]
+    fn expression_to_dot() {
+        let expression = Expression::new("abc|abd");
+
+        assert_eq!(expression.to_dot(true), concat!(
+            "digraph DFA {\n",
+            "    rankdir = LR;\n",
+            "    start [shape = point];\n",
+            "    1 [shape = circle];\n",
+            "    2 [shape = circle];\n",
+            "    3 [shape = circle];\n",
+            "    4 [shape = doublecircle];\n",
+            "    start -> 1;\n",
+            "    1 -> 2 [label = \"a\"];\n",
+            "    2 -> 3 [label = \"b\"];\n",
+            "    3 -> 4 [label = \"c, d\"];\n",
+            "}\n"
+        ));
+
+        let dot = expression.to_dot(false);
+
+        assert!(dot.contains("    0 [shape = circle];\n"));
+        assert!(dot.contains("    0 -> 0 [label = \"a-d\"];\n"));
+    }
+
+    #[test]
+    fn pipeline_to_dot() {
+        let nfa = NFA::from_string("a*").unwrap();
+        let nfa_dot = nfa.to_dot();
+        let dfa_dot = DFA::from_nfa(nfa).minimize().to_dot(false);
+
+        assert!(nfa_dot.starts_with("digraph NFA {\n"));
+        assert!(nfa_dot.contains("[label = \"ε\"]"));
+        assert!(dfa_dot.starts_with("digraph DFA {\n"));
+        assert!(!dfa_dot.contains("ε"));
+    }
+}
@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod tests {
+    use automata::expression::Expression;
+
+    // Checks that both expressions match the same strings up to a length
+    fn assert_equivalent(first: &Expression, second: &Expression) {
+        let first_strings: Vec<String> = first.strings_up_to(8).collect();
+        let second_strings: Vec<String> = second.strings_up_to(8).collect();
+
+        assert_eq!(first_strings, second_strings);
+    }
+
+    #[test]
+    fn expression_string_round_trip() {
+        let patterns = [
+            "abc", "ab?c", "ab*c", "ab+c", "ab|c", "Ivan|Petq", "a+bc*|ca*",
+            "a(bc|d)*e?", "(ab)+|ba", "a?b?c?", "\\*\\|a\\\\", "a*b*", "(a|b)*abb"
+        ];
+
+        for pattern in patterns.iter() {
+            let expression = Expression::parse(pattern).unwrap();
+            let expression_string = expression.to_expression_string();
+            let rebuilt = Expression::parse(&expression_string).unwrap();
+
+            assert_equivalent(&expression, &rebuilt);
+        }
+    }
+
+    #[test]
+    fn simple_expression_strings() {
+        assert_eq!(Expression::new("abc").to_expression_string(), "abc");
+        assert_eq!(Expression::new("ab*c").to_expression_string(), "ab*c");
+        assert_eq!(Expression::new("ab+").to_expression_string(), "ab+");
+        assert_eq!(Expression::new("a|b").to_expression_string(), "a|b");
+    }
+
+    #[test]
+    fn expression_strings_of_special_languages() {
+        let nothing = Expression::new("").to_expression_string();
+        let empty = Expression::parse("()").unwrap().to_expression_string();
+
+        assert_eq!(nothing, "");
+        assert_eq!(empty, "()");
+        assert_eq!(Expression::new("a*").to_expression_string(), "a*");
+        assert_eq!(Expression::parse("()?").unwrap().to_expression_string(), "()");
+        assert_eq!(Expression::parse("a|()").unwrap().to_expression_string(), "a?");
+
+        // Both round-trip and stay different
+        assert_eq!(Expression::parse(&nothing).unwrap().strings().count(), 0);
+        assert_eq!(Expression::parse(&empty).unwrap().strings().collect::<Vec<_>>(), vec![""]);
+        assert!(!Expression::parse(&nothing).unwrap().is_equivalent(&Expression::parse(&empty).unwrap()));
+    }
+}
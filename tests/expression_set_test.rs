@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use automata::expression::{Expression, ExpressionSet};
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn matches_readme_example() {
+        let set = ExpressionSet::new(&["ERROR (disk|net)", "(ERROR|WARN) net", "INFO ok"]);
+
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.matches("ERROR net").into_iter().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(set.matches("WARN net").into_iter().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(set.matches("INFO ok").into_iter().collect::<Vec<_>>(), vec![2]);
+        assert!(!set.is_match("INFO net"));
+    }
+
+    #[test]
+    fn agrees_with_single_expressions() {
+        let patterns = ["a+bc*", "ca*", "(a|b)*c", "ba+|ab", "a?b?c?", "(ab)+"];
+        let expressions: Vec<Expression> = patterns.iter().map(|pattern| Expression::parse(pattern).unwrap()).collect();
+        let set = ExpressionSet::new(&patterns);
+
+        for text in &["", "a", "ab", "abc", "ac", "c", "caa", "baa", "abab", "bbc", "abcc", "d"] {
+            let expected: BTreeSet<usize> = (0..patterns.len()).filter(|index| expressions[*index].matches(text)).collect();
+
+            assert_eq!(set.matches(text), expected, "text {:?}", text);
+            assert_eq!(set.is_match(text), !expected.is_empty());
+        }
+    }
+
+    #[test]
+    fn many_patterns() {
+        let patterns: Vec<String> = (0..200).map(|index| format!("id{}x*", index)).collect();
+        let set = ExpressionSet::new(&patterns);
+
+        assert_eq!(set.matches("id42xx").into_iter().collect::<Vec<_>>(), vec![42]);
+        assert_eq!(set.matches("id199").into_iter().collect::<Vec<_>>(), vec![199]);
+        assert!(!set.is_match("id200"));
+    }
+
+    #[test]
+    fn owned_patterns_and_errors() {
+        let patterns = vec!["a".to_string(), "+b".to_string()];
+        let error = ExpressionSet::parse(&patterns).unwrap_err();
+
+        assert_eq!(error.index, 1);
+    }
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "ffi")]
+
+#[cfg(test)]
+mod tests {
+    use automata::ffi::*;
+
+    const HEADER: &str = include_str!("../include/automata.h");
+    const SOURCE: &str = include_str!("../src/ffi.rs");
+
+    #[test]
+    fn header_declares_all_functions() {
+        let functions: Vec<&str> = SOURCE.lines()
+                                         .filter_map(|line| line.split("extern \"C\" fn ").nth(1))
+                                         .map(|rest| rest.split('(').next().unwrap())
+                                         .collect();
+
+        assert_eq!(functions.len(), 4);
+
+        for function in functions {
+            assert!(HEADER.contains(&format!(" {}(", function)) || HEADER.contains(&format!("*{}(", function)), "{} is not in the header", function);
+        }
+    }
+
+    #[test]
+    fn header_error_codes_match() {
+        let codes = [
+            ("AUTOMATA_OK", AUTOMATA_OK),
+            ("AUTOMATA_ERROR_NULL_POINTER", AUTOMATA_ERROR_NULL_POINTER),
+            ("AUTOMATA_ERROR_INVALID_UTF8", AUTOMATA_ERROR_INVALID_UTF8),
+            ("AUTOMATA_ERROR_MISSING_OPERAND", AUTOMATA_ERROR_MISSING_OPERAND),
+            ("AUTOMATA_ERROR_UNCLOSED_GROUP", AUTOMATA_ERROR_UNCLOSED_GROUP),
+            ("AUTOMATA_ERROR_UNMATCHED_PARENTHESIS", AUTOMATA_ERROR_UNMATCHED_PARENTHESIS),
+            ("AUTOMATA_ERROR_DANGLING_ESCAPE", AUTOMATA_ERROR_DANGLING_ESCAPE),
+            ("AUTOMATA_ERROR_INVALID_GROUP_NAME", AUTOMATA_ERROR_INVALID_GROUP_NAME),
+            ("AUTOMATA_ERROR_DUPLICATE_GROUP_NAME", AUTOMATA_ERROR_DUPLICATE_GROUP_NAME),
+            ("AUTOMATA_ERROR_NESTING_TOO_DEEP", AUTOMATA_ERROR_NESTING_TOO_DEEP),
+            ("AUTOMATA_ERROR_PANIC", AUTOMATA_ERROR_PANIC)
+        ];
+
+        let defined_codes = HEADER.lines()
+                                  .filter(|line| line.starts_with("#define AUTOMATA_") && line.split_whitespace().count() == 3)
+                                  .count();
+
+        assert_eq!(defined_codes, codes.len());
+
+        for (name, value) in codes.iter() {
+            assert!(HEADER.contains(&format!("#define {} {}\n", name, value)), "{} doesn't match the header", name);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use automata::expression::Expression;
+    use automata::fst::FstMap;
+
+    use std::collections::BTreeMap;
+    use std::ops::Bound;
+
+    // Most keys have a smaller value than an earlier key that they share a prefix with,
+    // so the output on the shared edges has to be pushed down the earlier key's path
+    const PUSHED_OUTPUTS: [(&str, u64); 10] = [
+        ("a", 50), ("ab", 40), ("abc", 60), ("abcd", 5), ("abd", 45),
+        ("b", 7), ("ba", 7), ("bb", 8), ("c", 100), ("ca", 1)
+    ];
+
+    // The smallest and the largest values on keys that share prefixes and suffixes
+    const EXTREME_VALUES: [(&str, u64); 8] = [
+        ("", u64::MAX), ("x", 0), ("xy", u64::MAX), ("xz", 0),
+        ("y", u64::MAX), ("yy", u64::MAX - 1), ("z", 0), ("zy", 0)
+    ];
+
+    fn owned(entries: &[(&str, u64)]) -> Vec<(String, u64)> {
+        entries.iter().map(|(key, value)| (key.to_string(), *value)).collect()
+    }
+
+    #[test]
+    fn lookups() {
+        for entries in [&PUSHED_OUTPUTS[..], &EXTREME_VALUES[..]].iter() {
+            let map = FstMap::from_sorted(entries.iter().cloned()).unwrap();
+
+            assert_eq!(map.len(), entries.len());
+
+            for (key, value) in entries.iter() {
+                assert_eq!(map.get(key), Some(*value), "{:?}", key);
+            }
+        }
+
+        let map = FstMap::from_sorted(PUSHED_OUTPUTS).unwrap();
+
+        assert_eq!(map.get(""), None);
+        assert_eq!(map.get("abcde"), None);
+        assert_eq!(map.get("ac"), None);
+        assert_eq!(map.get("bc"), None);
+        assert!(map.contains_key("abcd"));
+    }
+
+    #[test]
+    fn iteration_in_sorted_order() {
+        for entries in [&PUSHED_OUTPUTS[..], &EXTREME_VALUES[..]].iter() {
+            let map = FstMap::from_sorted(entries.iter().cloned()).unwrap();
+            assert_eq!(map.iter().collect::<Vec<_>>(), owned(entries));
+        }
+    }
+
+    #[test]
+    fn prefixes() {
+        let map = FstMap::from_sorted(PUSHED_OUTPUTS).unwrap();
+
+        assert_eq!(map.prefix("ab").collect::<Vec<_>>(), owned(&[("ab", 40), ("abc", 60), ("abcd", 5), ("abd", 45)]));
+        assert_eq!(map.prefix("abc").collect::<Vec<_>>(), owned(&[("abc", 60), ("abcd", 5)]));
+        assert_eq!(map.prefix("ac").count(), 0);
+        assert_eq!(map.prefix("").count(), map.len());
+
+        let map = FstMap::from_sorted(EXTREME_VALUES).unwrap();
+
+        assert_eq!(map.prefix("x").collect::<Vec<_>>(), owned(&[("x", 0), ("xy", u64::MAX), ("xz", 0)]));
+        assert_eq!(map.prefix("y").collect::<Vec<_>>(), owned(&[("y", u64::MAX), ("yy", u64::MAX - 1)]));
+    }
+
+    #[test]
+    fn ranges_between_keys() {
+        let map = FstMap::from_sorted(PUSHED_OUTPUTS).unwrap();
+
+        assert_eq!(map.range("aa".."abcc").collect::<Vec<_>>(), owned(&[("ab", 40), ("abc", 60)]));
+        assert_eq!(map.range("abca"..="bab").collect::<Vec<_>>(), owned(&[("abcd", 5), ("abd", 45), ("b", 7), ("ba", 7)]));
+        assert_eq!(map.range((Bound::Excluded("ab"), Bound::Included("b"))).collect::<Vec<_>>(), owned(&[("abc", 60), ("abcd", 5), ("abd", 45), ("b", 7)]));
+        assert_eq!(map.range("bz"..).collect::<Vec<_>>(), owned(&[("c", 100), ("ca", 1)]));
+        assert_eq!(map.range(.."aa").collect::<Vec<_>>(), owned(&[("a", 50)]));
+        assert_eq!(map.range("abcda".."abcz").count(), 0);
+        assert_eq!(map.range("cb"..).count(), 0);
+    }
+
+    #[test]
+    fn ranges_agree_with_btree_map() {
+        let map = FstMap::from_sorted(PUSHED_OUTPUTS).unwrap();
+        let entries: BTreeMap<&str, u64> = PUSHED_OUTPUTS.iter().cloned().collect();
+
+        // Keys and the strings between them
+        let bounds = ["", "a", "aa", "abc", "abcc", "abcda", "abz", "b", "bab", "bz", "ca", "cb"];
+
+        for start in bounds.iter() {
+            for end in bounds.iter().filter(|end| start <= end) {
+                let expected: Vec<_> = entries.range::<&str, _>(start..end).map(|(key, value)| (key.to_string(), *value)).collect();
+                assert_eq!(map.range(*start..*end).collect::<Vec<_>>(), expected, "{:?}..{:?}", start, end);
+
+                let expected: Vec<_> = entries.range::<&str, _>(start..=end).map(|(key, value)| (key.to_string(), *value)).collect();
+                assert_eq!(map.range(*start..=*end).collect::<Vec<_>>(), expected, "{:?}..={:?}", start, end);
+            }
+        }
+    }
+
+    #[test]
+    fn searching_with_expressions() {
+        let map = FstMap::from_sorted(PUSHED_OUTPUTS).unwrap();
+
+        let expression = Expression::parse("ab(c|d)*").unwrap();
+        assert_eq!(map.search(&expression).collect::<Vec<_>>(), owned(&[("ab", 40), ("abc", 60), ("abcd", 5), ("abd", 45)]));
+
+        let expression = Expression::parse("(b|c)a?").unwrap();
+        assert_eq!(map.search(&expression).collect::<Vec<_>>(), owned(&[("b", 7), ("ba", 7), ("c", 100), ("ca", 1)]));
+
+        let map = FstMap::from_sorted(EXTREME_VALUES).unwrap();
+
+        let expression = Expression::parse("(x|y|z)y").unwrap();
+        assert_eq!(map.search(&expression).collect::<Vec<_>>(), owned(&[("xy", u64::MAX), ("yy", u64::MAX - 1), ("zy", 0)]));
+    }
+}
@@ -0,0 +1,21 @@
+pub fn generated_matches(text: &str) -> bool {
+    let mut state: u32 = 2;
+
+    for ch in text.chars() {
+        state = match (state, ch) {
+            (1, 'x'..='z') => 1,
+            (1, 'é') => 4,
+            (2, 'a') => 3,
+            (2, 'c') => 6,
+            (2, 'x'..='z') => 1,
+            (3, 'a') => 3,
+            (3, 'b') => 5,
+            (5, 'c') => 5,
+            (6, '*') => 6,
+            (6, 'a') => 6,
+            _ => return false
+        };
+    }
+
+    matches!(state, 4..=6)
+}
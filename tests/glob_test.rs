@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use automata::glob::{Glob, GlobErrorKind, GlobSet};
+
+    #[test]
+    fn single_star_and_question_mark() {
+        let glob = Glob::new("src/*.r?");
+
+        assert!(glob.matches("src/main.rs"));
+        assert!(glob.matches("src/.rb"));
+        assert!(glob.matches("src/é.rs"));
+        assert!(!glob.matches("src/main.r"));
+        assert!(!glob.matches("src/bin/main.rs"));
+        assert!(!glob.matches("src/main.r/"));
+    }
+
+    #[test]
+    fn double_star() {
+        let glob = Glob::new("src/**/*.rs");
+
+        assert!(glob.matches("src/lib.rs"));
+        assert!(glob.matches("src/bin/main.rs"));
+        assert!(glob.matches("src/a/b/c.rs"));
+        assert!(!glob.matches("lib.rs"));
+        assert!(!glob.matches("src/lib.rs/x"));
+
+        assert!(Glob::new("**/test").matches("test"));
+        assert!(Glob::new("**/test").matches("a/b/test"));
+        assert!(!Glob::new("**/test").matches("a/btest"));
+        assert!(Glob::new("target/**").matches("target/debug/build"));
+        assert!(!Glob::new("target/**").matches("target"));
+    }
+
+    #[test]
+    fn classes() {
+        let glob = Glob::new("file[0-9][!a-c].txt");
+
+        assert!(glob.matches("file1d.txt"));
+        assert!(glob.matches("file9_.txt"));
+        assert!(!glob.matches("file1a.txt"));
+        assert!(!glob.matches("filex1.txt"));
+        assert!(!glob.matches("file1/.txt"));
+    }
+
+    #[test]
+    fn braces() {
+        let glob = Glob::new("*.{rs,toml,{c,h}{,pp}}");
+
+        for path in &["lib.rs", "Cargo.toml", "a.c", "a.h", "a.cpp", "a.hpp"] {
+            assert!(glob.matches(path), "{}", path);
+        }
+
+        assert!(!glob.matches("a.py"));
+        assert!(!glob.matches("a.cp"));
+    }
+
+    #[test]
+    fn escapes_and_literals() {
+        assert!(Glob::new("\\*\\?").matches("*?"));
+        assert!(!Glob::new("\\*\\?").matches("ab"));
+        assert!(Glob::new("").matches(""));
+        assert!(!Glob::new("").matches("a"));
+        assert!(Glob::new("a,b}").matches("a,b}"));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Glob::parse("*.[ch").unwrap_err().kind, GlobErrorKind::UnclosedClass);
+        assert_eq!(Glob::parse("*.{c,h").unwrap_err().kind, GlobErrorKind::UnclosedBraces);
+        assert_eq!(Glob::parse("[b-a]").unwrap_err().to_string(), "range at position 1 ends before it starts");
+    }
+
+    #[test]
+    fn glob_set() {
+        let set = GlobSet::new(&["**/*.rs", "src/**", "*.md", "docs/[a-m]*"]);
+
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.matches("src/lib.rs").into_iter().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(set.matches("README.md").into_iter().collect::<Vec<_>>(), vec![2]);
+        assert_eq!(set.matches("docs/guide.md").into_iter().collect::<Vec<_>>(), vec![3]);
+        assert!(!set.is_match("docs/tutorial.txt"));
+        assert!(!set.is_match("Cargo.toml"));
+
+        let error = GlobSet::parse(&["*", "{a"]).unwrap_err();
+        assert_eq!(error.index, 1);
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    use automata::expression::Expression;
+    use automata::levenshtein::LevenshteinAutomaton;
+
+    fn dictionary() -> Expression {
+        Expression::new("apple|apply|ample|maple|angle|apples|banana")
+    }
+
+    #[test]
+    fn intersect_with_dictionary() {
+        let suggestions = LevenshteinAutomaton::new("appel", 2).intersect(&dictionary());
+        let strings: Vec<String> = suggestions.strings().collect();
+
+        assert_eq!(strings, vec!["apple", "apply", "apples"]);
+        assert!(suggestions.matches("apple"));
+        assert!(!suggestions.matches("appel"));
+        assert!(!suggestions.matches("ample"));
+    }
+
+    #[test]
+    fn suggestions_closest_first() {
+        let suggestions = LevenshteinAutomaton::new("aple", 1).suggestions(&dictionary());
+
+        assert_eq!(suggestions, vec![("ample".to_string(), 1), ("apple".to_string(), 1), ("maple".to_string(), 1)]);
+        assert_eq!(LevenshteinAutomaton::new("apple", 1).suggestions(&dictionary()), vec![
+            ("apple".to_string(), 0),
+            ("ample".to_string(), 1),
+            ("apply".to_string(), 1),
+            ("apples".to_string(), 1)
+        ]);
+    }
+
+    #[test]
+    fn no_suggestions() {
+        let suggestions = LevenshteinAutomaton::new("zzz", 1).intersect(&dictionary());
+
+        assert_eq!(suggestions.strings().count(), 0);
+        assert!(LevenshteinAutomaton::new("xyz", 2).suggestions(&dictionary()).is_empty());
+    }
+
+    #[test]
+    fn infinite_dictionary() {
+        let expression = Expression::new("ab*c");
+        let strings: Vec<String> = LevenshteinAutomaton::new("abbbc", 1).intersect(&expression).strings().collect();
+
+        assert_eq!(strings, vec!["abbc", "abbbc", "abbbbc"]);
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use automata::lexer::{Lexer, LexError, Rule, Token};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Kind {
+        Let,
+        Name,
+        Number,
+        Plus,
+        Times,
+        Assign,
+        LeftParen,
+        RightParen
+    }
+
+    // Returns an alternation of all chars of the string, e.g. "(a|b|c)"
+    fn one_of(chars: &str) -> String {
+        let escaped: Vec<String> = chars.chars().map(|ch| {
+            if "|?*+()\\".contains(ch) { format!("\\{}", ch) } else { ch.to_string() }
+        }).collect();
+
+        format!("({})", escaped.join("|"))
+    }
+
+    fn lexer() -> Lexer<Kind> {
+        let letters = one_of("abcdefghijklmnopqrstuvwxyz_");
+        let digits = one_of("0123456789");
+
+        Lexer::new(vec![
+            Rule::token(Kind::Let, "let"),
+            Rule::token(Kind::Name, &format!("{}({}|{})*", letters, letters, digits)),
+            Rule::token(Kind::Number, &format!("{}+", digits)),
+            Rule::token(Kind::Plus, "\\+"),
+            Rule::token(Kind::Times, "\\*"),
+            Rule::token(Kind::Assign, "="),
+            Rule::token(Kind::LeftParen, "\\("),
+            Rule::token(Kind::RightParen, "\\)"),
+            Rule::skip(&format!("{}+", one_of(" \t\n"))),
+            Rule::skip(&format!("#{}*", one_of("abcdefghijklmnopqrstuvwxyz0123456789 +*=()"))),
+        ])
+    }
+
+    #[test]
+    fn tokenize_program() {
+        let text = "let x1 = (2 + y) * 10 # the answer\nlet letter = x1";
+        let tokens = lexer().tokenize(text).unwrap();
+        let pieces: Vec<(Kind, &str)> = tokens.iter().map(|token| (token.kind, &text[token.span.clone()])).collect();
+
+        assert_eq!(pieces, vec![
+            (Kind::Let, "let"),
+            (Kind::Name, "x1"),
+            (Kind::Assign, "="),
+            (Kind::LeftParen, "("),
+            (Kind::Number, "2"),
+            (Kind::Plus, "+"),
+            (Kind::Name, "y"),
+            (Kind::RightParen, ")"),
+            (Kind::Times, "*"),
+            (Kind::Number, "10"),
+            (Kind::Let, "let"),
+            (Kind::Name, "letter"),
+            (Kind::Assign, "="),
+            (Kind::Name, "x1")
+        ]);
+    }
+
+    #[test]
+    fn report_unrecognized_input() {
+        let lexer = lexer();
+
+        assert_eq!(lexer.tokenize("x = 1 - 2"), Err(LexError { position: 6, unexpected: '-' }));
+
+        let results: Vec<_> = lexer.tokens("1 $ 2").collect();
+        assert_eq!(results, vec![
+            Ok(Token { kind: Kind::Number, span: 0..1 }),
+            Err(LexError { position: 2, unexpected: '$' }),
+            Ok(Token { kind: Kind::Number, span: 4..5 })
+        ]);
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod tests {
+    use automata::expression::Expression;
+
+    #[test]
+    fn feed_in_chunks() {
+        let expression = Expression::new("a+bc*|ca*");
+        let mut matcher = expression.matcher();
+
+        for chunk in &["a", "aab", "", "cc"] {
+            matcher.feed(chunk);
+        }
+
+        assert!(matcher.is_accepting());
+        assert!(!matcher.is_dead());
+
+        matcher.feed("a");
+        assert!(matcher.is_dead());
+        assert!(!matcher.is_accepting());
+    }
+
+    #[test]
+    fn chunks_agree_with_matches() {
+        let expression = Expression::parse("(a|b)*c|ba+").unwrap();
+
+        for text in &["", "c", "abc", "baa", "bac", "ca", "abab", "bbbbc"] {
+            let mut matcher = expression.matcher();
+
+            for ch in text.chars() {
+                matcher.feed_char(ch);
+            }
+
+            assert_eq!(matcher.is_accepting(), expression.matches(text));
+        }
+    }
+
+    #[test]
+    fn reuse_after_reset() {
+        let expression = Expression::new("ab");
+        let mut matcher = expression.matcher();
+
+        matcher.feed("abab");
+        assert!(matcher.is_dead());
+
+        matcher.reset();
+        matcher.feed("ab");
+        assert!(matcher.is_accepting());
+    }
+
+    #[test]
+    fn try_alternatives_from_snapshot() {
+        let expression = Expression::parse("key=(on|off)").unwrap();
+        let mut matcher = expression.matcher();
+
+        matcher.feed("key=");
+        let snapshot = matcher.snapshot();
+
+        let accepted: Vec<&str> = ["on", "off", "of", "yes"].iter().cloned().filter(|value| {
+            matcher.restore(snapshot);
+            matcher.feed(value);
+            matcher.is_accepting()
+        }).collect();
+
+        assert_eq!(accepted, vec!["on", "off"]);
+    }
+}
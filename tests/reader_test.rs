@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use automata::expression::Expression;
+    use std::io::{self, Read};
+
+    // Produces a huge text without keeping it in memory
+    struct Repeat {
+        byte: u8,
+        remaining: u64
+    }
+
+    impl Read for Repeat {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            let length = buffer.len().min(self.remaining as usize);
+
+            for byte in &mut buffer[..length] {
+                *byte = self.byte;
+            }
+
+            self.remaining -= length as u64;
+            Ok(length)
+        }
+    }
+
+    #[test]
+    fn matches_reader() {
+        let expression = Expression::new("a+bc*|ca*");
+
+        assert!(expression.matches_reader("aaabcc".as_bytes()).unwrap());
+        assert!(expression.matches_reader("caaa".as_bytes()).unwrap());
+        assert!(!expression.matches_reader("aaab".repeat(2).as_bytes()).unwrap());
+        assert!(!expression.matches_reader("".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn matches_large_reader() {
+        let expression = Expression::new("a*");
+        let reader = Repeat { byte: b'a', remaining: 1_000_000 };
+
+        assert!(expression.matches_reader(reader).unwrap());
+    }
+
+    #[test]
+    fn stops_reading_when_dead() {
+        let expression = Expression::new("ab");
+        let reader = Repeat { byte: b'b', remaining: u64::MAX };
+
+        assert!(!expression.matches_reader(reader).unwrap());
+    }
+
+    #[test]
+    fn find_in_reader() {
+        let expression = Expression::new("ab+c");
+
+        assert_eq!(expression.find_in_reader("xxabbbcab".as_bytes()).unwrap(), Some(2..7));
+        assert_eq!(expression.find_in_reader("éabcé".as_bytes()).unwrap(), Some(2..5));
+        assert_eq!(expression.find_in_reader("abab".as_bytes()).unwrap(), None);
+    }
+
+    #[test]
+    fn find_leftmost_longest_match() {
+        assert_eq!(Expression::new("bc|abcd").find_in_reader("abcd".as_bytes()).unwrap(), Some(0..4));
+        assert_eq!(Expression::new("ab|abc|b").find_in_reader("xxabcz".as_bytes()).unwrap(), Some(2..5));
+        assert_eq!(Expression::new("a*").find_in_reader("baa".as_bytes()).unwrap(), Some(0..0));
+        assert_eq!(Expression::new("a+").find_in_reader("baa".as_bytes()).unwrap(), Some(1..3));
+    }
+
+    #[test]
+    fn find_in_reader_agrees_with_find() {
+        let texts = ["", "a", "ab", "xxabcz", "baab", "abcabcd", "bcbcbc", "éaébc"];
+
+        for pattern in &["ab|abc|b", "a+", "a*", "bc|abcd", "(ab)+c?", "é?b"] {
+            let expression = Expression::parse(pattern).unwrap();
+
+            for text in texts.iter() {
+                let expected = expression.find(text).map(|range| range.start as u64..range.end as u64);
+                assert_eq!(expression.find_in_reader(text.as_bytes()).unwrap(), expected, "{:?} in {:?}", pattern, text);
+            }
+        }
+    }
+
+    #[test]
+    fn find_at_end_of_large_reader() {
+        let expression = Expression::new("ab");
+        let reader = Repeat { byte: b'a', remaining: 100_000 }.chain("b".as_bytes());
+
+        assert_eq!(expression.find_in_reader(reader).unwrap(), Some(99_999..100_001));
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let error = Expression::new("a*").matches_reader(&b"aa\xff"[..]).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
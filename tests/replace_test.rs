@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use automata::expression::Expression;
+
+    #[test]
+    fn find() {
+        let expression = Expression::parse("(0|1|2|3|4|5|6|7|8|9)+").unwrap();
+        let text = "width 120, height 80";
+
+        assert_eq!(expression.find(text), Some(6..9));
+        assert_eq!(expression.find_iter(text).map(|range| &text[range]).collect::<Vec<_>>(), vec!["120", "80"]);
+        assert_eq!(expression.find("none"), None);
+    }
+
+    #[test]
+    fn replace_with_strings() {
+        let expression = Expression::new("cat|dog");
+
+        assert_eq!(expression.replace_all("cat and dog and catfish", "pet"), "pet and pet and petfish");
+        assert_eq!(expression.replace_all("a cat", "[$0]"), "a [cat]");
+        assert_eq!(expression.replace_all("a cat", String::from("$$5")), "a $5");
+        assert_eq!(expression.replace_all("no pets", "pet"), "no pets");
+    }
+
+    #[test]
+    fn replace_with_closures() {
+        let expression = Expression::parse("(a|b)+").unwrap();
+        let mut count = 0;
+
+        let replaced = expression.replace_all("ab, ba, c, bbb", |matched: &str| {
+            count += 1;
+            format!("{}{}", matched.len(), matched.to_uppercase())
+        });
+
+        assert_eq!(replaced, "2AB, 2BA, c, 3BBB");
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn replace_n() {
+        let expression = Expression::new("o");
+
+        assert_eq!(expression.replace_n("foo boo", 3, "0"), "f00 b0o");
+        assert_eq!(expression.replace_n("foo boo", 0, "0"), "foo boo");
+        assert_eq!(expression.replace_n("foo boo", 10, "0"), "f00 b00");
+    }
+
+    #[test]
+    fn replace_empty_matches() {
+        let expression = Expression::new("x*");
+
+        assert_eq!(expression.replace_all("abxxc", "-"), "-a-b-c-");
+        assert_eq!(expression.replace_all("", "-"), "-");
+        assert_eq!(expression.replace_all("é", "-"), "-é-");
+    }
+
+    #[test]
+    fn capture_groups() {
+        let expression = Expression::parse("(?<key>(a|b)+)=((0|1)*)").unwrap();
+
+        assert_eq!(expression.replace_all("ab=10, b=1", "$3:${key}"), "10:ab, 1:b");
+        assert_eq!(expression.replace_all("a=0", "$2 $4 $5 ${value}"), "a 0 $5 ${value}");
+        assert_eq!(expression.replace_all("b=", "[$3]"), "[]");
+
+        let captures = expression.captures("x ab=10").unwrap();
+        assert_eq!(captures.group_count(), 5);
+        assert_eq!(captures.range(0), Some(2..7));
+        assert_eq!(captures.name("key"), Some("ab"));
+        assert_eq!(captures.get(3), Some("10"));
+        assert_eq!(captures.get(5), None);
+        assert_eq!(captures.name("value"), None);
+    }
+
+    #[test]
+    fn capture_groups_in_long_matches() {
+        let expression = Expression::parse("(a*)(b)").unwrap();
+        let text = "a".repeat(20_000) + "b";
+
+        assert_eq!(expression.captures(&text).unwrap().range(1), Some(0..20_000));
+    }
+}
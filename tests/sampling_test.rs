@@ -0,0 +1,51 @@
+#![cfg(feature = "rand")]
+
+#[cfg(test)]
+mod tests {
+    use automata::expression::Expression;
+
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn sample_matches_expression() {
+        let expression = Expression::new("a+bc*|ca*");
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for length in 1..10 {
+            let string = expression.sample(&mut rng, length).unwrap();
+
+            assert_eq!(string.chars().count(), length);
+            assert!(expression.matches(&string));
+        }
+    }
+
+    #[test]
+    fn sample_covers_all_strings() {
+        let expression = Expression::new("a?b?c?d?");
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut found = Vec::new();
+
+        for _ in 0..200 {
+            found.push(expression.sample(&mut rng, 2).unwrap());
+        }
+
+        found.sort();
+        found.dedup();
+
+        assert_eq!(found, expression.strings().filter(|s| s.len() == 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn random_walk_matches_expression() {
+        let expression = Expression::new("a+bc*|ca*");
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for _ in 0..100 {
+            let string = expression.random_walk(&mut rng, 0.3).unwrap();
+            assert!(expression.matches(&string));
+        }
+
+        assert_eq!(Expression::new("").random_walk(&mut rng, 0.3), None);
+    }
+}
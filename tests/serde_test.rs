@@ -0,0 +1,48 @@
+#![cfg(feature = "serde")]
+
+#[cfg(test)]
+mod tests {
+    use automata::expression::Expression;
+
+    #[test]
+    fn expression_round_trip() {
+        let expression = Expression::new("a+bc*|ca*");
+        assert!(expression.contains_match("xxabx"));
+        let json = serde_json::to_string(&expression).unwrap();
+        let loaded: Expression = serde_json::from_str(&json).unwrap();
+
+        assert!(loaded.matches("aabcc"));
+        assert!(loaded.matches("caa"));
+        assert!(!loaded.matches("bc"));
+        assert!(loaded.contains_match("xxabx"));
+        assert!(!loaded.contains_match("bbb"));
+        assert_eq!(loaded.to_expression_string(), expression.to_expression_string());
+    }
+
+    #[test]
+    fn capture_groups_round_trip() {
+        // Only expressions with groups carry the automaton that finds them
+        assert!(!serde_json::to_string(&Expression::new("a+b*")).unwrap().contains("groups"));
+
+        let expression = Expression::parse("(?<first>a+)(b*)").unwrap();
+        let json = serde_json::to_string(&expression).unwrap();
+        assert!(json.contains("groups"));
+        let loaded: Expression = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.replace_all("aab", "$2${first}"), "baa");
+
+        // Expressions serialized before capture groups existed have none
+        let json = r#"{"dfa":{"alphabet":["a"],"states":[0],"transitions":[{"from":0,"label":"a","to":0}],"final_states":[0],"initial_state":0}}"#;
+        let loaded: Expression = serde_json::from_str(json).unwrap();
+
+        assert_eq!(loaded.replace_all("aa", "<$1$0>"), "<$1aa>");
+    }
+
+    #[test]
+    fn invalid_expression_is_rejected() {
+        let json = r#"{"dfa":{"alphabet":["a"],"states":[0],"transitions":[{"from":0,"label":"a","to":1}],"final_states":[0],"initial_state":0}}"#;
+        let error = serde_json::from_str::<Expression>(json).err().unwrap();
+
+        assert!(error.to_string().contains("state 1"));
+    }
+}
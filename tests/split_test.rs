@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests {
+    use automata::expression::Expression;
+
+    #[test]
+    fn irregular_delimiters() {
+        let delimiter = Expression::parse(" *(,|;|\\|) *").unwrap();
+
+        assert_eq!(delimiter.split("a, b;c |d").collect::<Vec<_>>(), vec!["a", "b", "c", "d"]);
+        assert_eq!(delimiter.split("a,,b").collect::<Vec<_>>(), vec!["a", "", "b"]);
+        assert_eq!(delimiter.split(", a ;").collect::<Vec<_>>(), vec!["", "a", ""]);
+        assert_eq!(delimiter.split("").collect::<Vec<_>>(), vec![""]);
+    }
+
+    #[test]
+    fn splitn() {
+        let delimiter = Expression::new(" *, *");
+
+        assert_eq!(delimiter.splitn("name, 12 , a, b", 3).collect::<Vec<_>>(), vec!["name", "12", "a, b"]);
+        assert_eq!(delimiter.splitn("name, 12", 5).collect::<Vec<_>>(), vec!["name", "12"]);
+        assert_eq!(delimiter.splitn("name, 12", 1).collect::<Vec<_>>(), vec!["name, 12"]);
+        assert_eq!(delimiter.splitn("name, 12", 0).count(), 0);
+    }
+
+    #[test]
+    fn same_as_str_split() {
+        let expression = Expression::new("ab");
+
+        for text in &["", "ab", "abab", "xabyab", "aab b", "éabé"] {
+            assert_eq!(expression.split(text).collect::<Vec<_>>(), text.split("ab").collect::<Vec<_>>(), "{:?}", text);
+
+            for count in 0..4 {
+                assert_eq!(expression.splitn(text, count).collect::<Vec<_>>(), text.splitn(count, "ab").collect::<Vec<_>>(), "{:?}", text);
+            }
+        }
+    }
+
+    #[test]
+    fn empty_matches() {
+        let expression = Expression::new("-*");
+
+        assert_eq!(expression.split("ab--c").collect::<Vec<_>>(), vec!["", "a", "b", "c", ""]);
+        assert_eq!(expression.split("é").collect::<Vec<_>>(), "é".split("").collect::<Vec<_>>());
+        assert_eq!(expression.splitn("abc", 2).collect::<Vec<_>>(), vec!["", "abc"]);
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use automata::expression::Expression;
+
+    #[test]
+    fn strings_of_finite_expression() {
+        let expression = Expression::new("ab?c|b");
+        let strings: Vec<String> = expression.strings().collect();
+
+        assert_eq!(strings, vec!["b", "ac", "abc"]);
+    }
+
+    #[test]
+    fn strings_of_infinite_expression() {
+        let expression = Expression::new("a+bc*|ca*");
+        let strings: Vec<String> = expression.strings().take(7).collect();
+
+        assert_eq!(strings, vec!["c", "ab", "ca", "aab", "abc", "caa", "aaab"]);
+    }
+
+    #[test]
+    fn strings_up_to_length() {
+        let expression = Expression::new("ab*");
+        let strings: Vec<String> = expression.strings_up_to(3).collect();
+
+        assert_eq!(strings, vec!["a", "ab", "abb"]);
+    }
+
+    #[test]
+    fn strings_are_matched_by_expression() {
+        let expression = Expression::new("a*b?c+|ba+");
+
+        for string in expression.strings_up_to(6) {
+            assert!(expression.matches(&string));
+        }
+    }
+
+    #[test]
+    fn count_of_length() {
+        let expression = Expression::new("a+bc*|ca*");
+
+        assert_eq!(expression.count_of_length(0), Some(0));
+        assert_eq!(expression.count_of_length(1), Some(1));
+        assert_eq!(expression.count_of_length(2), Some(2));
+        assert_eq!(expression.count_of_length(3), Some(3));
+    }
+
+    #[test]
+    fn count_up_to_agrees_with_strings() {
+        let expression = Expression::new("a*b?c+|ba+");
+
+        for length in 0..6 {
+            let strings_count = expression.strings_up_to(length).count() as u128;
+            assert_eq!(expression.count_up_to(length), Some(strings_count));
+        }
+    }
+
+    #[test]
+    fn count_overflow() {
+        let expression = Expression::new("a*|b*|c*|d*");
+
+        assert_eq!(expression.count_of_length(1000), Some(4));
+
+        let expression = Expression::new("a*b*c*d*e*f*g*h*i*j*k*l*m*n*o*p*q*r*s*t*u*v*w*x*y*z*");
+
+        assert!(expression.count_of_length(100).is_some());
+        assert_eq!(expression.count_of_length(1000), None);
+        assert_eq!(expression.count_up_to(1000), None);
+    }
+
+    #[test]
+    fn count_overflow_of_states_that_are_not_final() {
+        // The states before the 'c' overflow at length 128, but no string of that length is accepted yet
+        let expression = Expression::parse(&format!("{}c", "(a|b)".repeat(128))).unwrap();
+
+        assert_eq!(expression.count_of_length(128), Some(0));
+        assert_eq!(expression.count_up_to(128), Some(0));
+        assert_eq!(expression.count_of_length(129), None);
+        assert_eq!(expression.count_up_to(129), None);
+    }
+
+    #[test]
+    fn count_huge_lengths() {
+        assert_eq!(Expression::new("ab|c").count_of_length(usize::MAX), Some(0));
+        assert_eq!(Expression::new("ab|c").count_up_to(usize::MAX), Some(2));
+        assert_eq!(Expression::new("a*|b*").count_of_length(usize::MAX), Some(2));
+        assert_eq!(Expression::new("a*").count_up_to(usize::MAX), Some(usize::MAX as u128 + 1));
+        assert_eq!(Expression::parse("(a|b)*").unwrap().count_of_length(usize::MAX), None);
+        assert_eq!(Expression::parse("(a|b)*").unwrap().count_up_to(usize::MAX), None);
+    }
+}
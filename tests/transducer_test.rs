@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use automata::transducer::{MealyMachine, MooreMachine};
+
+    // All strings of 0s and 1s up to the length
+    fn inputs(max_length: usize) -> Vec<String> {
+        let mut res = vec![String::new()];
+        let mut last = vec![String::new()];
+
+        for _ in 0..max_length {
+            last = last.iter().flat_map(|input| vec![format!("{}0", input), format!("{}1", input)]).collect();
+            res.extend(last.clone());
+        }
+
+        res
+    }
+
+    // Manchester code: each bit is sent as a transition between levels
+    fn manchester() -> MealyMachine<&'static str> {
+        let mut machine = MealyMachine::new();
+
+        machine.add_transition(0, '0', 0, "10");
+        machine.add_transition(0, '1', 0, "01");
+        machine
+    }
+
+    // Inverts every other bit, with redundant states for the phase
+    fn alternating_inverter() -> MealyMachine<char> {
+        let mut machine = MealyMachine::new();
+        let odd = machine.add_state();
+        let even = machine.add_state();
+        let odd_again = machine.add_state();
+
+        machine.add_transition(0, '0', odd, '0');
+        machine.add_transition(0, '1', odd, '1');
+        machine.add_transition(odd, '0', even, '1');
+        machine.add_transition(odd, '1', even, '0');
+        machine.add_transition(even, '0', odd_again, '0');
+        machine.add_transition(even, '1', odd_again, '1');
+        machine.add_transition(odd_again, '0', 0, '1');
+        machine.add_transition(odd_again, '1', 0, '0');
+        machine
+    }
+
+    #[test]
+    fn encoding() {
+        let encoded: String = manchester().run("1101").unwrap().concat();
+
+        assert_eq!(encoded, "01011001");
+        assert_eq!(manchester().run("12"), None);
+    }
+
+    #[test]
+    fn composition_runs_one_machine_after_the_other() {
+        let first = alternating_inverter();
+        let second = manchester();
+        let composed = first.compose(&second);
+
+        for input in inputs(6) {
+            let middle: String = first.run(&input).unwrap().into_iter().collect();
+            assert_eq!(composed.run(&input), second.run(&middle), "{}", input);
+        }
+    }
+
+    #[test]
+    fn moore_composition() {
+        let mut parity = MooreMachine::new(false);
+        let odd = parity.add_state(true);
+
+        // Reads the digits of the first machine and tells whether their sum is odd
+        for (state, flipped) in &[(0, odd), (odd, 0)] {
+            parity.add_transition(*state, '0', *state);
+            parity.add_transition(*state, '2', *state);
+            parity.add_transition(*state, '1', *flipped);
+        }
+
+        // Outputs the number of 1s seen so far modulo 3
+        let mut digits = MooreMachine::new('0');
+        let one = digits.add_state('1');
+        let two = digits.add_state('2');
+
+        for (state, next) in &[(0, one), (one, two), (two, 0)] {
+            digits.add_transition(*state, '0', *state);
+            digits.add_transition(*state, '1', *next);
+        }
+
+        let composed = digits.compose(&parity);
+
+        for input in inputs(6) {
+            let middle: String = digits.run(&input).unwrap().into_iter().skip(1).collect();
+            assert_eq!(composed.run(&input), parity.run(&middle), "{}", input);
+        }
+    }
+
+    #[test]
+    fn minimization_keeps_the_outputs() {
+        let machine = alternating_inverter();
+        let minimized = machine.clone().minimize();
+
+        assert_eq!(machine.state_count(), 4);
+        assert_eq!(minimized.state_count(), 2);
+
+        for input in inputs(6) {
+            assert_eq!(minimized.run(&input), machine.run(&input), "{}", input);
+        }
+
+        let composed = machine.compose(&manchester());
+        assert_eq!(composed.clone().minimize().state_count(), 2);
+    }
+}